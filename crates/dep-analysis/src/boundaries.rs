@@ -0,0 +1,104 @@
+use petgraph::graph::DiGraph;
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+use std::fmt;
+
+use dep_core::{EdgeData, Node, NodeKind, resolve_node_kind};
+
+use crate::dependencies::{owner, package_manifests};
+use crate::paths::Hop;
+use crate::{ImportFilter, import_graph};
+
+/// An import of another package's file by relative path or alias, bypassing
+/// the package's name and public entry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BoundaryViolation {
+    /// Package owning the importing file
+    pub package: String,
+    /// Package owning the imported file
+    pub target: String,
+    /// The specifier as written in the import
+    pub specifier: String,
+    pub import: Hop,
+}
+
+impl fmt::Display for BoundaryViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} reaches into {} with '{}' -> {}",
+            self.import.location.as_ref().map_or(self.import.from.clone(), |l| l.to_string()),
+            self.package,
+            self.target,
+            self.specifier,
+            self.import.to
+        )
+    }
+}
+
+/// Whether `specifier` goes through the package name (`@acme/ui` or
+/// `@acme/ui/button`) or the importing package's own `#imports` map.
+fn through_package(specifier: &str, package: &str) -> bool {
+    specifier.starts_with('#')
+        || specifier
+            .strip_prefix(package)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Find imports, among those selected by `imports`, from a file of one
+/// package to a file of another that don't name the imported package:
+/// relative paths like `../../ui/src/button` and tsconfig aliases. Files of
+/// an enclosing package, such as the repository root, are not considered
+/// another package. Sorted by importing file, then line.
+pub fn boundary_violations(graph: &DiGraph<Node, EdgeData>, imports: ImportFilter) -> Vec<BoundaryViolation> {
+    let manifests = package_manifests(graph);
+    let g = import_graph(graph, imports);
+    let mut violations = Vec::new();
+    for edge in g.edge_references() {
+        if !matches!(resolve_node_kind(graph, edge.target()), NodeKind::File | NodeKind::Asset) {
+            continue;
+        }
+        let Some(specifier) = &edge.weight().specifier else {
+            continue;
+        };
+        let (Some(source), Some(target)) = (
+            owner(&manifests, &g[edge.source()].name),
+            owner(&manifests, &g[edge.target()].name),
+        ) else {
+            continue;
+        };
+        if source.package == target.package || target.contains(&source.dir) {
+            continue;
+        }
+        let target_name = &graph[target.package].name;
+        if through_package(specifier, target_name) {
+            continue;
+        }
+        violations.push(BoundaryViolation {
+            package: graph[source.package].name.clone(),
+            target: target_name.clone(),
+            specifier: specifier.clone(),
+            import: Hop::new(&g, edge.id()),
+        });
+    }
+    violations.sort_by(|a, b| {
+        let line = |v: &BoundaryViolation| v.import.location.as_ref().map(|l| (l.line, l.column));
+        (&a.import.from, line(a)).cmp(&(&b.import.from, line(b)))
+    });
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_through_package() {
+        assert!(through_package("@acme/ui", "@acme/ui"));
+        assert!(through_package("@acme/ui/button", "@acme/ui"));
+        assert!(through_package("#ui", "@acme/ui"));
+        assert!(!through_package("@acme/ui-kit", "@acme/ui"));
+        assert!(!through_package("../../ui/src/button", "@acme/ui"));
+        assert!(!through_package("@ui/button", "@acme/ui"));
+    }
+}
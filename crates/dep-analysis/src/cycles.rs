@@ -0,0 +1,142 @@
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use dep_core::{EdgeData, Node};
+
+use crate::{ImportFilter, import_graph};
+
+/// An import cycle: one strongly connected component of the import graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Every file in the component, sorted
+    pub files: Vec<String>,
+    /// The shortest loop through the component's first file, which is also
+    /// the last entry
+    pub path: Vec<String>,
+}
+
+impl Cycle {
+    /// Whether the cycle was already known: some baseline entry contains all
+    /// of its files. A cycle that lost files is still known, one that gained
+    /// files is not.
+    pub fn is_known(&self, baseline: &[Vec<String>]) -> bool {
+        baseline
+            .iter()
+            .any(|known| self.files.iter().all(|f| known.contains(f)))
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.join(" -> "))
+    }
+}
+
+/// Find the import cycles in a dependency graph, sorted by their files.
+/// Only import edges selected by `imports` are followed, see [`import_graph`].
+pub fn find_cycles(graph: &DiGraph<Node, EdgeData>, imports: ImportFilter) -> Vec<Cycle> {
+    let g = import_graph(graph, imports);
+    let mut cycles = Vec::new();
+    for component in tarjan_scc(&g) {
+        if component.len() == 1 && g.find_edge(component[0], component[0]).is_none() {
+            continue;
+        }
+        let members: HashSet<NodeIndex> = component.iter().copied().collect();
+        let mut files: Vec<String> = component.iter().map(|i| g[*i].name.clone()).collect();
+        files.sort();
+        let start = component
+            .iter()
+            .copied()
+            .min_by(|a, b| g[*a].name.cmp(&g[*b].name))
+            .unwrap();
+        let path = shortest_loop(&g, start, &members)
+            .into_iter()
+            .map(|i| g[i].name.clone())
+            .collect();
+        cycles.push(Cycle { files, path });
+    }
+    cycles.sort_by(|a, b| a.files.cmp(&b.files));
+    cycles
+}
+
+/// Breadth-first search from `start` back to itself, staying inside the
+/// component. Neighbours are visited by name so the loop is stable.
+fn shortest_loop(
+    g: &DiGraph<Node, EdgeData>,
+    start: NodeIndex,
+    members: &HashSet<NodeIndex>,
+) -> Vec<NodeIndex> {
+    let mut prev: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(idx) = queue.pop_front() {
+        let mut next: Vec<NodeIndex> = g.neighbors(idx).filter(|n| members.contains(n)).collect();
+        next.sort_by(|a, b| g[*a].name.cmp(&g[*b].name));
+        next.dedup();
+        for n in next {
+            if n == start {
+                let mut path = vec![start, idx];
+                let mut cur = idx;
+                while let Some(&p) = prev.get(&cur) {
+                    path.push(p);
+                    cur = p;
+                }
+                path.reverse();
+                return path;
+            }
+            if let Entry::Vacant(e) = prev.entry(n) {
+                e.insert(idx);
+                queue.push_back(n);
+            }
+        }
+    }
+    vec![start]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::test_util::import;
+    use dep_core::{EdgeType, ImportKind};
+
+    #[test]
+    fn test_find_cycles_through_barrel() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let a = g.add_node(Node { name: "a.ts".into() });
+        let folder = g.add_node(Node { name: "lib".into() });
+        let index = g.add_node(Node { name: "lib/index.ts".into() });
+        let b = g.add_node(Node { name: "b.ts".into() });
+        let c = g.add_node(Node { name: "c.ts".into() });
+        g.add_edge(folder, index, EdgeType::SameAs.into());
+        g.add_edge(folder, a, EdgeType::Regular.into());
+        g.add_edge(a, folder, import(ImportKind::Static));
+        g.add_edge(index, a, import(ImportKind::Static));
+        g.add_edge(b, c, import(ImportKind::Static));
+        g.add_edge(c, b, import(ImportKind::Type));
+
+        let cycles = find_cycles(&g, ImportFilter::All);
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0].to_string(), "a.ts -> lib/index.ts -> a.ts");
+        assert_eq!(cycles[1].to_string(), "b.ts -> c.ts -> b.ts");
+
+        let runtime = find_cycles(&g, ImportFilter::Runtime);
+        assert_eq!(runtime.len(), 1);
+        assert!(runtime[0].is_known(&[vec!["a.ts".into(), "lib/index.ts".into(), "x.ts".into()]]));
+        assert!(!runtime[0].is_known(&[vec!["a.ts".into()]]));
+    }
+
+    #[test]
+    fn test_find_cycles_self_import() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let a = g.add_node(Node { name: "self.ts".into() });
+        g.add_edge(a, a, import(ImportKind::Static));
+
+        let cycles = find_cycles(&g, ImportFilter::All);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].files, ["self.ts"]);
+        assert_eq!(cycles[0].to_string(), "self.ts -> self.ts");
+        assert_eq!(cycles[0].path.len(), cycles[0].files.len() + 1);
+    }
+}
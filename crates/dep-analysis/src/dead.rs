@@ -0,0 +1,161 @@
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::{Bfs, EdgeRef, Walker};
+use std::collections::HashSet;
+
+use dep_core::{EdgeData, EdgeType, Node, NodeKind};
+use dep_core::{is_type_node, resolve_node_kind};
+
+use crate::{ImportFilter, import_graph, matches_entrypoint, same_as_target};
+
+/// Files that tools load by themselves: tests and tool configuration.
+pub const IMPLICIT_ENTRYPOINTS: &[&str] = &[
+    "**/*.test.*",
+    "**/*.spec.*",
+    "**/__tests__/**",
+    "**/*.config.*",
+];
+
+/// Manifests, workspace files and lockfiles read by package managers
+/// rather than imported. They are never reported as dead, even when they
+/// are not entrypoints.
+pub const MANIFEST_FILES: &[&str] = &[
+    "package.json",
+    "pnpm-workspace.yaml",
+    "pnpm-workspace.yml",
+    "lerna.json",
+    "rush.json",
+    "pnpm-lock.yaml",
+    "package-lock.json",
+    "yarn.lock",
+];
+
+fn is_manifest(name: &str) -> bool {
+    MANIFEST_FILES.contains(&name.rsplit('/').next().unwrap_or(name))
+}
+
+/// Collect the files a project is entered from: every node matching the
+/// `patterns` globs and, when `infer` is set, package manifests with the
+/// files they point to (`main`, `module`, `bin`, `exports`), HTML pages and
+/// [`IMPLICIT_ENTRYPOINTS`].
+pub fn find_entrypoints(
+    graph: &DiGraph<Node, EdgeData>,
+    patterns: &[String],
+    infer: bool,
+) -> Vec<NodeIndex> {
+    let implicit: Vec<String> = IMPLICIT_ENTRYPOINTS.iter().map(|p| p.to_string()).collect();
+    let mut entries = Vec::new();
+    for idx in graph.node_indices() {
+        let node = &graph[idx];
+        if is_type_node(node) {
+            continue;
+        }
+        let kind = resolve_node_kind(graph, idx);
+        if matches!(kind, NodeKind::Folder | NodeKind::Symbol) {
+            continue;
+        }
+        if matches_entrypoint(&node.name, patterns) {
+            entries.push(idx);
+        } else if infer && kind == NodeKind::Package {
+            entries.extend(
+                graph
+                    .edges(idx)
+                    .filter(|e| e.weight().kind == EdgeType::Regular && e.weight().import_kind.is_none())
+                    .map(|e| e.target()),
+            );
+        } else if infer
+            && kind == NodeKind::File
+            && (node.name == "package.json"
+                || node.name.ends_with("/package.json")
+                || node.name.ends_with(".html")
+                || matches_entrypoint(&node.name, &implicit))
+        {
+            entries.push(idx);
+        }
+    }
+    entries.sort_by(|a, b| graph[*a].name.cmp(&graph[*b].name));
+    entries.dedup();
+    entries
+}
+
+/// List the files and assets that no entrypoint reaches through imports
+/// selected by `imports`, sorted by name. [`MANIFEST_FILES`] are skipped.
+pub fn dead_files(
+    graph: &DiGraph<Node, EdgeData>,
+    entrypoints: &[NodeIndex],
+    imports: ImportFilter,
+) -> Vec<NodeIndex> {
+    let g = import_graph(graph, imports);
+    let mut reached = HashSet::new();
+    for &entry in entrypoints {
+        let entry = same_as_target(graph, entry);
+        if reached.contains(&entry) {
+            continue;
+        }
+        reached.extend(Bfs::new(&g, entry).iter(&g));
+    }
+    let mut dead: Vec<NodeIndex> = graph
+        .node_indices()
+        .filter(|i| !is_type_node(&graph[*i]) && !reached.contains(i) && !is_manifest(&graph[*i].name))
+        .filter(|i| matches!(resolve_node_kind(graph, *i), NodeKind::File | NodeKind::Asset))
+        .collect();
+    dead.sort_by(|a, b| graph[*a].name.cmp(&graph[*b].name));
+    dead
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::ImportKind;
+    use dep_core::test_util::import;
+
+    #[test]
+    fn test_dead_files_ignore_folder_edges() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let folder_type = g.add_node(Node { name: NodeKind::Folder.type_node_name().into() });
+        let asset_type = g.add_node(Node { name: NodeKind::Asset.type_node_name().into() });
+        let src = g.add_node(Node { name: "src".into() });
+        let main = g.add_node(Node { name: "src/main.ts".into() });
+        let used = g.add_node(Node { name: "src/used.ts".into() });
+        let orphan = g.add_node(Node { name: "src/orphan.ts".into() });
+        let logo = g.add_node(Node { name: "src/logo.svg".into() });
+        let test = g.add_node(Node { name: "src/used.test.ts".into() });
+        g.add_edge(src, folder_type, EdgeType::TypeOf.into());
+        g.add_edge(logo, asset_type, EdgeType::TypeOf.into());
+        for file in [main, used, orphan, logo, test] {
+            g.add_edge(src, file, EdgeType::Regular.into());
+        }
+        g.add_edge(main, used, import(ImportKind::Static));
+        g.add_edge(orphan, logo, import(ImportKind::SideEffect));
+        g.add_edge(test, used, import(ImportKind::Static));
+
+        let names = |entries: Vec<NodeIndex>| {
+            dead_files(&g, &entries, ImportFilter::All)
+                .into_iter()
+                .map(|i| g[i].name.as_str())
+                .collect::<Vec<_>>()
+        };
+        let entries = find_entrypoints(&g, &["src/main.ts".into()], true);
+        assert_eq!(entries, [main, test]);
+        assert_eq!(names(entries), ["src/logo.svg", "src/orphan.ts"]);
+
+        let entries = find_entrypoints(&g, &["src/main.ts".into()], false);
+        assert_eq!(names(entries), ["src/logo.svg", "src/orphan.ts", "src/used.test.ts"]);
+    }
+
+    #[test]
+    fn test_dead_files_skip_manifests() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let manifest = g.add_node(Node { name: "packages/ui/package.json".into() });
+        let main = g.add_node(Node { name: "packages/ui/index.ts".into() });
+        g.add_edge(manifest, main, EdgeType::Regular.into());
+        g.add_node(Node { name: "pnpm-lock.yaml".into() });
+        g.add_node(Node { name: "pnpm-workspace.yaml".into() });
+
+        let entries = find_entrypoints(&g, &[], false);
+        let dead: Vec<_> = dead_files(&g, &entries, ImportFilter::All)
+            .into_iter()
+            .map(|i| g[i].name.as_str())
+            .collect();
+        assert_eq!(dead, ["packages/ui/index.ts"]);
+    }
+}
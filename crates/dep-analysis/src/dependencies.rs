@@ -0,0 +1,225 @@
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use dep_core::js_resolve::{is_remote, split_bare_specifier};
+use dep_core::{DependencyKind, EdgeData, EdgeType, Node, NodeKind, resolve_node_kind};
+
+use crate::paths::Hop;
+use crate::{ImportFilter, import_graph};
+
+/// An import of a package that the importing file's package does not
+/// declare in any of its dependency fields.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MissingDependency {
+    /// Package owning the importing file
+    pub package: String,
+    /// Name of the imported package, without any subpath
+    pub dependency: String,
+    pub import: Hop,
+}
+
+impl fmt::Display for MissingDependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} is not declared ({})", self.package, self.dependency, self.import)
+    }
+}
+
+/// A declared dependency that no file of the package imports.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UnusedDependency {
+    pub package: String,
+    pub dependency: String,
+    pub kind: DependencyKind,
+    pub version: String,
+}
+
+impl fmt::Display for UnusedDependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} {}@{} is never imported", self.package, self.kind, self.dependency, self.version)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DependencyReport {
+    pub missing: Vec<MissingDependency>,
+    pub unused: Vec<UnusedDependency>,
+}
+
+impl DependencyReport {
+    /// Drop the dependencies matching an `ignore` name or glob pattern, such
+    /// as tools only run from package.json scripts, and the unused ones
+    /// whose kind is not in `kinds` (all kinds when empty). Missing
+    /// dependencies are declared nowhere, so they have no kind to filter on.
+    pub fn filter(&mut self, kinds: &[DependencyKind], ignore: &[String]) {
+        let patterns: Vec<_> = ignore.iter().map(|p| glob::Pattern::new(p)).collect();
+        let ignored = |name: &str| {
+            ignore.iter().zip(&patterns).any(|(p, pattern)| match pattern {
+                Ok(pattern) => pattern.matches(name),
+                Err(_) => name == p,
+            })
+        };
+        self.missing.retain(|m| !ignored(&m.dependency));
+        self.unused
+            .retain(|u| !ignored(&u.dependency) && (kinds.is_empty() || kinds.contains(&u.kind)));
+    }
+}
+
+/// A package and the folder of its `package.json`, relative to the root.
+pub(crate) struct Manifest {
+    pub(crate) dir: String,
+    pub(crate) package: NodeIndex,
+}
+
+impl Manifest {
+    pub(crate) fn contains(&self, file: &str) -> bool {
+        self.dir.is_empty()
+            || file
+                .strip_prefix(self.dir.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// Find the packages of the graph from the edges linking each
+/// `package.json` to the package it declares.
+pub(crate) fn package_manifests(graph: &DiGraph<Node, EdgeData>) -> Vec<Manifest> {
+    let mut manifests = Vec::new();
+    for edge in graph.edge_references() {
+        let file = &graph[edge.source()].name;
+        if edge.weight().kind == EdgeType::Regular
+            && edge.weight().import_kind.is_none()
+            && edge.weight().dependency.is_none()
+            && (file == "package.json" || file.ends_with("/package.json"))
+            && resolve_node_kind(graph, edge.target()) == NodeKind::Package
+        {
+            let dir = file.strip_suffix("package.json").unwrap_or_default();
+            manifests.push(Manifest {
+                dir: dir.trim_end_matches('/').to_string(),
+                package: edge.target(),
+            });
+        }
+    }
+    manifests
+}
+
+/// The package owning `file`: the one with the deepest folder.
+pub(crate) fn owner<'a>(manifests: &'a [Manifest], file: &str) -> Option<&'a Manifest> {
+    manifests
+        .iter()
+        .filter(|m| m.contains(file))
+        .max_by_key(|m| m.dir.len())
+}
+
+/// Name of the `@types` package providing types for `name`.
+fn types_package(name: &str) -> String {
+    match name.strip_prefix('@') {
+        Some(scoped) => format!("@types/{}", scoped.replace('/', "__")),
+        None => format!("@types/{name}"),
+    }
+}
+
+/// Cross-reference the imports selected by `imports` with the dependencies
+/// declared by the package owning each file. Deep imports count for their
+/// package (`lodash/fp` uses `lodash`), and importing another package's
+/// files needs that package as a dependency. Declared `@types/*` packages
+/// are never reported unused, and they cover type-only imports of the
+/// package they type.
+pub fn dependency_report(graph: &DiGraph<Node, EdgeData>, imports: ImportFilter) -> DependencyReport {
+    let manifests = package_manifests(graph);
+    let mut declared: HashMap<NodeIndex, Vec<(String, DependencyKind, String)>> = HashMap::new();
+    for edge in graph.edge_references() {
+        if let Some(dep) = &edge.weight().dependency {
+            declared.entry(edge.source()).or_default().push((
+                graph[edge.target()].name.clone(),
+                dep.kind,
+                dep.version.clone(),
+            ));
+        }
+    }
+
+    let g = import_graph(graph, imports);
+    let mut used: HashSet<(NodeIndex, String)> = HashSet::new();
+    let mut report = DependencyReport::default();
+    for edge in g.edge_references() {
+        let from = &g[edge.source()].name;
+        let Some(source) = owner(&manifests, from) else {
+            continue;
+        };
+        let to = &g[edge.target()].name;
+        let dependency = match resolve_node_kind(graph, edge.target()) {
+            // Scripts and stylesheets loaded from a CDN are not installed
+            NodeKind::External if is_remote(to) => continue,
+            NodeKind::External => split_bare_specifier(to).0.to_string(),
+            NodeKind::File | NodeKind::Asset => {
+                let Some(target) = owner(&manifests, to) else {
+                    continue;
+                };
+                // Reaching into an enclosing package, such as the repository
+                // root, is not a package import
+                if target.package == source.package || target.contains(&source.dir) {
+                    continue;
+                }
+                graph[target.package].name.clone()
+            }
+            _ => continue,
+        };
+        let deps = declared.get(&source.package).map(Vec::as_slice).unwrap_or_default();
+        let is_declared = |name: &str| deps.iter().any(|(d, _, _)| d == name);
+        let type_only = edge.weight().import_kind.is_some_and(|k| k.is_type_only());
+        if is_declared(&dependency) {
+            used.insert((source.package, dependency));
+        } else if !(type_only && is_declared(&types_package(&dependency)))
+            && dependency != graph[source.package].name
+        {
+            report.missing.push(MissingDependency {
+                package: graph[source.package].name.clone(),
+                dependency,
+                import: Hop::new(&g, edge.id()),
+            });
+        }
+    }
+
+    for manifest in &manifests {
+        for (name, kind, version) in declared.get(&manifest.package).into_iter().flatten() {
+            if name.starts_with("@types/") || used.contains(&(manifest.package, name.clone())) {
+                continue;
+            }
+            report.unused.push(UnusedDependency {
+                package: graph[manifest.package].name.clone(),
+                dependency: name.clone(),
+                kind: *kind,
+                version: version.clone(),
+            });
+        }
+    }
+
+    report.missing.sort_by(|a, b| {
+        let line = |m: &MissingDependency| m.import.location.as_ref().map(|l| l.line);
+        (&a.package, &a.import.from, line(a), &a.dependency).cmp(&(&b.package, &b.import.from, line(b), &b.dependency))
+    });
+    report.unused.sort_by(|a, b| (&a.package, &a.dependency, a.kind).cmp(&(&b.package, &b.dependency, b.kind)));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owner_and_types_package() {
+        let manifests = [
+            Manifest { dir: String::new(), package: NodeIndex::new(0) },
+            Manifest { dir: "packages/app".into(), package: NodeIndex::new(1) },
+            Manifest { dir: "packages/app-utils".into(), package: NodeIndex::new(2) },
+        ];
+        let package = |file| owner(&manifests, file).map(|m| m.package.index());
+        assert_eq!(package("packages/app/src/main.ts"), Some(1));
+        assert_eq!(package("packages/app-utils/index.ts"), Some(2));
+        assert_eq!(package("scripts/build.js"), Some(0));
+
+        assert_eq!(types_package("react"), "@types/react");
+        assert_eq!(types_package("@babel/core"), "@types/babel__core");
+    }
+}
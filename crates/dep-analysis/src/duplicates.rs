@@ -0,0 +1,116 @@
+use petgraph::Direction;
+use petgraph::graph::DiGraph;
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+use dep_core::{EdgeData, EdgeType, Node};
+
+/// A third-party package installed in several versions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DuplicatePackage {
+    pub name: String,
+    /// Installed versions, oldest first
+    pub versions: Vec<InstalledVersion>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct InstalledVersion {
+    pub version: String,
+    /// Packages that pull in this version: project packages and other
+    /// installed package versions
+    pub dependents: Vec<String>,
+}
+
+/// Compare versions by their numeric parts, so `1.10.0` comes after `1.9.0`.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<(u64, String)> {
+        v.split(['.', '-', '+'])
+            .map(|p| (p.parse().unwrap_or(u64::MAX), p.to_string()))
+            .collect()
+    };
+    parts(a).cmp(&parts(b))
+}
+
+/// Find packages with more than one version installed, from the `Resolved`
+/// edges added by lockfiles. Sorted by name.
+pub fn duplicate_packages(graph: &DiGraph<Node, EdgeData>) -> Vec<DuplicatePackage> {
+    let mut duplicates = Vec::new();
+    for idx in graph.node_indices() {
+        let name = &graph[idx].name;
+        let prefix = format!("{name}@");
+        let mut versions: Vec<InstalledVersion> = graph
+            .edges(idx)
+            .filter(|e| e.weight().kind == EdgeType::Resolved)
+            .filter_map(|e| {
+                let version = graph[e.target()].name.strip_prefix(&prefix)?.to_string();
+                let mut dependents: Vec<String> = graph
+                    .edges_directed(e.target(), Direction::Incoming)
+                    .filter(|d| d.source() != idx)
+                    .filter(|d| {
+                        d.weight().dependency.is_some() || d.weight().kind == EdgeType::Resolved
+                    })
+                    .map(|d| graph[d.source()].name.clone())
+                    .collect();
+                dependents.sort();
+                dependents.dedup();
+                Some(InstalledVersion { version, dependents })
+            })
+            .collect();
+        versions.sort_by(|a, b| compare_versions(&a.version, &b.version));
+        versions.dedup_by(|a, b| a.version == b.version);
+        if versions.len() > 1 {
+            duplicates.push(DuplicatePackage {
+                name: name.clone(),
+                versions,
+            });
+        }
+    }
+    duplicates.sort_by(|a, b| a.name.cmp(&b.name));
+    duplicates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::{Dependency, DependencyKind};
+
+    #[test]
+    fn test_duplicate_packages() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let mut node = |name: &str| g.add_node(Node { name: name.into() });
+        let app = node("app");
+        let debug = node("debug");
+        let debug4 = node("debug@4.3.4");
+        let ms = node("ms");
+        let ms_old = node("ms@2.0.0");
+        let ms_9 = node("ms@2.9.0");
+        let ms_new = node("ms@2.10.0");
+        let dep = |version: &str| EdgeData {
+            dependency: Some(Dependency { kind: DependencyKind::Prod, version: version.into() }),
+            ..EdgeType::Regular.into()
+        };
+        g.add_edge(debug, debug4, EdgeType::Resolved.into());
+        for v in [ms_old, ms_9, ms_new] {
+            g.add_edge(ms, v, EdgeType::Resolved.into());
+        }
+        g.add_edge(app, ms_old, EdgeType::Resolved.into());
+        g.add_edge(debug4, ms_new, dep("2.10.0"));
+
+        let duplicates = duplicate_packages(&g);
+        assert_eq!(duplicates.len(), 1);
+        let versions: Vec<_> = duplicates[0]
+            .versions
+            .iter()
+            .map(|v| (v.version.as_str(), v.dependents.clone()))
+            .collect();
+        assert_eq!(
+            versions,
+            [
+                ("2.0.0", vec!["app".to_string()]),
+                ("2.9.0", vec![]),
+                ("2.10.0", vec!["debug@4.3.4".to_string()]),
+            ]
+        );
+    }
+}
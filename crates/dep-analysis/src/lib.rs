@@ -1,10 +1,31 @@
-use dep_core::{EdgeType, Node, NodeKind};
+pub mod boundaries;
+pub mod cycles;
+pub mod dead;
+pub mod dependencies;
+pub mod duplicates;
+pub mod paths;
+pub mod rules;
+pub mod symbols;
+pub mod unresolved;
+
+pub use boundaries::{BoundaryViolation, boundary_violations};
+pub use cycles::{Cycle, find_cycles};
+pub use dead::{IMPLICIT_ENTRYPOINTS, dead_files, find_entrypoints};
+pub use dependencies::{DependencyReport, MissingDependency, UnusedDependency, dependency_report};
+pub use duplicates::{DuplicatePackage, InstalledVersion, duplicate_packages};
+pub use paths::{Dependent, Hop, dependents, import_paths};
+pub use rules::{Rule, Violation, check_rules};
+pub use symbols::{ExportResolver, SymbolUse, UnusedExport, matches_entrypoint, symbol_graph};
+pub use symbols::{symbol_users, symbol_uses, unused_exports};
+pub use unresolved::{UnresolvedImport, unresolved_imports};
+
+use dep_core::{DependencyKind, EdgeData, EdgeType, Node, NodeKind};
 use dep_core::{is_type_node, resolve_node_kind};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-pub fn prune_unconnected(graph: &mut DiGraph<Node, EdgeType>) {
+pub fn prune_unconnected(graph: &mut DiGraph<Node, EdgeData>) {
     loop {
         let mut removed = false;
         let nodes: Vec<NodeIndex> = graph.node_indices().collect();
@@ -30,20 +51,53 @@ pub fn prune_unconnected(graph: &mut DiGraph<Node, EdgeType>) {
     }
 }
 
+/// Selects import edges by whether they only exist at the type level.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImportFilter {
+    /// Keep every import edge
+    #[default]
+    All,
+    /// Drop type-only imports, leaving the runtime graph
+    Runtime,
+    /// Keep only type-only imports
+    Types,
+}
+
+impl ImportFilter {
+    fn keep(&self, edge: &EdgeData) -> bool {
+        let Some(kind) = edge.import_kind else {
+            return true;
+        };
+        match self {
+            ImportFilter::All => true,
+            ImportFilter::Runtime => !kind.is_type_only(),
+            ImportFilter::Types => kind.is_type_only(),
+        }
+    }
+}
+
+/// Which nodes and edges [`filter_graph`] keeps. The default keeps files
+/// and unresolved imports, with every import edge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterOptions {
+    pub include_external: bool,
+    pub include_builtin: bool,
+    pub include_folders: bool,
+    pub include_assets: bool,
+    pub include_packages: bool,
+    pub include_symbols: bool,
+    /// Names of nodes to drop
+    pub ignore_nodes: Vec<String>,
+    pub imports: ImportFilter,
+    /// Kinds of package dependency edges to keep, or all when empty
+    pub dependency_kinds: Vec<DependencyKind>,
+}
+
 /// Filter a dependency graph according to output options.
-pub fn filter_graph(
-    graph: &DiGraph<Node, EdgeType>,
-    include_external: bool,
-    include_builtin: bool,
-    include_folders: bool,
-    include_assets: bool,
-    include_packages: bool,
-    ignore_nodes: &[String],
-) -> DiGraph<Node, EdgeType> {
-    let mut filtered: DiGraph<Node, EdgeType> = DiGraph::new();
+pub fn filter_graph(graph: &DiGraph<Node, EdgeData>, options: &FilterOptions) -> DiGraph<Node, EdgeData> {
+    let mut filtered: DiGraph<Node, EdgeData> = DiGraph::new();
     let mut map = HashMap::new();
-    use std::collections::HashSet;
-    let ignore: HashSet<&str> = ignore_nodes.iter().map(|s| s.as_str()).collect();
+    let ignore: HashSet<&str> = options.ignore_nodes.iter().map(|s| s.as_str()).collect();
 
     // First pass: add type singleton nodes (always include them for type resolution)
     for idx in graph.node_indices() {
@@ -65,12 +119,14 @@ pub fn filter_graph(
         }
         let kind = resolve_node_kind(graph, idx);
         let keep = match kind {
-            NodeKind::External => include_external,
-            NodeKind::Builtin => include_builtin,
-            NodeKind::File => true,
-            NodeKind::Folder => include_folders,
-            NodeKind::Asset => include_assets,
-            NodeKind::Package => include_packages,
+            NodeKind::External => options.include_external,
+            NodeKind::Builtin => options.include_builtin,
+            // Broken imports are always shown
+            NodeKind::File | NodeKind::Unresolved => true,
+            NodeKind::Folder => options.include_folders,
+            NodeKind::Asset => options.include_assets,
+            NodeKind::Package => options.include_packages,
+            NodeKind::Symbol => options.include_symbols,
         };
         if keep {
             let nidx = filtered.add_node(node.clone());
@@ -80,6 +136,15 @@ pub fn filter_graph(
 
     // Add edges
     for edge in graph.edge_references() {
+        if !options.imports.keep(edge.weight()) {
+            continue;
+        }
+        if let Some(dep) = &edge.weight().dependency
+            && !options.dependency_kinds.is_empty()
+            && !options.dependency_kinds.contains(&dep.kind)
+        {
+            continue;
+        }
         if let (Some(&s), Some(&t)) = (map.get(&edge.source()), map.get(&edge.target())) {
             filtered.add_edge(s, t, edge.weight().clone());
         }
@@ -87,18 +152,46 @@ pub fn filter_graph(
     filtered
 }
 
+/// Reduce a dependency graph to its import edges. Folder containment,
+/// package, `Export`, `SameAs` and `TypeOf` edges are dropped, and imports of
+/// a barrel folder are redirected to the `index` file it stands for. Node
+/// indices are the same as in `graph`.
+pub fn import_graph(graph: &DiGraph<Node, EdgeData>, imports: ImportFilter) -> DiGraph<Node, EdgeData> {
+    let mut out = graph.filter_map(|_, node| Some(node.clone()), |_, _| None::<EdgeData>);
+    for edge in graph.edge_references() {
+        let weight = edge.weight();
+        if weight.import_kind.is_none() || !imports.keep(weight) {
+            continue;
+        }
+        out.add_edge(edge.source(), same_as_target(graph, edge.target()), weight.clone());
+    }
+    out
+}
+
+/// Follow `SameAs` edges from a node to the file it stands for.
+pub(crate) fn same_as_target(graph: &DiGraph<Node, EdgeData>, mut idx: NodeIndex) -> NodeIndex {
+    let mut seen = HashSet::new();
+    while seen.insert(idx) {
+        match graph.edges(idx).find(|e| e.weight().kind == EdgeType::SameAs) {
+            Some(edge) => idx = edge.target(),
+            None => break,
+        }
+    }
+    idx
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dep_core::{Node, NodeKind, EdgeType};
+    use dep_core::{EdgeData, EdgeType, Node, NodeKind};
     use petgraph::graph::DiGraph;
 
     #[test]
     fn test_prune_unconnected() {
-        let mut g: DiGraph<Node, EdgeType> = DiGraph::new();
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
         let a = g.add_node(Node { name: "a".into() });
         let b = g.add_node(Node { name: "b".into() });
-        g.add_edge(a, b, EdgeType::Regular);
+        g.add_edge(a, b, EdgeType::Regular.into());
         let _c = g.add_node(Node { name: "c".into() });
         prune_unconnected(&mut g);
         assert!(g.node_indices().all(|i| g[i].name != "c"));
@@ -108,7 +201,7 @@ mod tests {
 
     #[test]
     fn test_filter_graph_with_types() {
-        let mut g: DiGraph<Node, EdgeType> = DiGraph::new();
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
 
         let ext_type = g.add_node(Node { name: NodeKind::External.type_node_name().into() });
         let builtin_type = g.add_node(Node { name: NodeKind::Builtin.type_node_name().into() });
@@ -120,18 +213,138 @@ mod tests {
         let ext = g.add_node(Node { name: "ext".into() });
         let builtin = g.add_node(Node { name: "builtin".into() });
 
-        g.add_edge(ext, ext_type, EdgeType::TypeOf);
-        g.add_edge(builtin, builtin_type, EdgeType::TypeOf);
+        g.add_edge(ext, ext_type, EdgeType::TypeOf.into());
+        g.add_edge(builtin, builtin_type, EdgeType::TypeOf.into());
 
-        g.add_edge(file, ext, EdgeType::Regular);
-        g.add_edge(file, builtin, EdgeType::Regular);
+        g.add_edge(file, ext, EdgeType::Regular.into());
+        g.add_edge(file, builtin, EdgeType::Regular.into());
 
-        let filtered = filter_graph(&g, false, true, true, true, true, &[]);
+        let filtered = filter_graph(
+            &g,
+            &FilterOptions {
+                include_builtin: true,
+                include_folders: true,
+                include_assets: true,
+                include_packages: true,
+                ..Default::default()
+            },
+        );
         assert!(!filtered.node_indices().any(|i| filtered[i].name == "ext"));
         assert!(filtered.node_indices().any(|i| filtered[i].name == "builtin"));
 
-        let filtered = filter_graph(&g, true, false, true, true, true, &[]);
+        let filtered = filter_graph(
+            &g,
+            &FilterOptions {
+                include_external: true,
+                include_folders: true,
+                include_assets: true,
+                include_packages: true,
+                ..Default::default()
+            },
+        );
         assert!(filtered.node_indices().any(|i| filtered[i].name == "ext"));
         assert!(!filtered.node_indices().any(|i| filtered[i].name == "builtin"));
     }
+
+    #[test]
+    fn test_filter_graph_type_imports() {
+        use dep_core::ImportKind;
+        use dep_core::test_util::import;
+
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let a = g.add_node(Node { name: "a.ts".into() });
+        let b = g.add_node(Node { name: "b.ts".into() });
+        let c = g.add_node(Node { name: "c.ts".into() });
+        g.add_edge(a, b, import(ImportKind::Static));
+        g.add_edge(a, c, import(ImportKind::Type));
+
+        let runtime = filter_graph(
+            &g,
+            &FilterOptions {
+                include_external: true,
+                include_builtin: true,
+                include_folders: true,
+                include_assets: true,
+                include_packages: true,
+                imports: ImportFilter::Runtime,
+                ..Default::default()
+            },
+        );
+        assert_eq!(runtime.edge_count(), 1);
+        assert_eq!(runtime[runtime.edge_indices().next().unwrap()].import_kind, Some(ImportKind::Static));
+
+        let types = filter_graph(
+            &g,
+            &FilterOptions {
+                include_external: true,
+                include_builtin: true,
+                include_folders: true,
+                include_assets: true,
+                include_packages: true,
+                imports: ImportFilter::Types,
+                ..Default::default()
+            },
+        );
+        assert_eq!(types.edge_count(), 1);
+        assert_eq!(types[types.edge_indices().next().unwrap()].import_kind, Some(ImportKind::Type));
+
+        let all = filter_graph(
+            &g,
+            &FilterOptions {
+                include_external: true,
+                include_builtin: true,
+                include_folders: true,
+                include_assets: true,
+                include_packages: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(all.edge_count(), 2);
+    }
+
+    #[test]
+    fn test_filter_graph_dependency_kinds() {
+        use dep_core::Dependency;
+
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let app = g.add_node(Node { name: "app".into() });
+        let react = g.add_node(Node { name: "react".into() });
+        let vitest = g.add_node(Node { name: "vitest".into() });
+        let entry = g.add_node(Node { name: "index.js".into() });
+        let dependency = |kind, version: &str| EdgeData {
+            dependency: Some(Dependency { kind, version: version.into() }),
+            ..EdgeType::Regular.into()
+        };
+        g.add_edge(app, react, dependency(DependencyKind::Prod, "^18.0.0"));
+        g.add_edge(app, vitest, dependency(DependencyKind::Dev, "^1.0.0"));
+        g.add_edge(app, entry, EdgeType::Regular.into());
+
+        let prod = filter_graph(
+            &g,
+            &FilterOptions {
+                include_external: true,
+                include_builtin: true,
+                include_folders: true,
+                include_assets: true,
+                include_packages: true,
+                dependency_kinds: vec![DependencyKind::Prod],
+                ..Default::default()
+            },
+        );
+        let targets: Vec<_> = prod.edge_references().map(|e| prod[e.target()].name.as_str()).collect();
+        assert_eq!(targets, ["react", "index.js"]);
+
+        let all = filter_graph(
+            &g,
+            &FilterOptions {
+                include_external: true,
+                include_builtin: true,
+                include_folders: true,
+                include_assets: true,
+                include_packages: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(all.edge_count(), 3);
+    }
 }
@@ -0,0 +1,256 @@
+use petgraph::Direction;
+use petgraph::graph::{DiGraph, EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use dep_core::{EdgeData, ImportKind, Location, Node, is_type_node};
+
+use crate::{ImportFilter, import_graph, matches_entrypoint, same_as_target};
+
+/// One import along a chain of files: `from` imports `to`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Hop {
+    pub from: String,
+    pub to: String,
+    pub import_kind: Option<ImportKind>,
+    /// Where `from` imports `to`
+    pub location: Option<Location>,
+}
+
+impl Hop {
+    pub(crate) fn new(graph: &DiGraph<Node, EdgeData>, edge: EdgeIndex) -> Self {
+        let (from, to) = graph.edge_endpoints(edge).unwrap();
+        let weight = &graph[edge];
+        Hop {
+            from: graph[from].name.clone(),
+            to: graph[to].name.clone(),
+            import_kind: weight.import_kind,
+            location: weight.location.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Hop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(loc) => write!(f, "{loc} -> {}", self.to),
+            None => write!(f, "{} -> {}", self.from, self.to),
+        }
+    }
+}
+
+/// A file that transitively imports the queried file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dependent {
+    pub file: String,
+    /// Number of imports between `file` and the queried file
+    pub depth: usize,
+    /// A shortest import chain from `file` to the queried file
+    pub chain: Vec<Hop>,
+}
+
+/// Find every file that imports `file`, directly or through other files,
+/// up to `max_depth` imports away. Results are sorted by depth, then name.
+/// Returns `None` when `file` is not in the graph.
+pub fn dependents(
+    graph: &DiGraph<Node, EdgeData>,
+    file: &str,
+    max_depth: Option<usize>,
+    imports: ImportFilter,
+) -> Option<Vec<Dependent>> {
+    let target = graph.node_indices().find(|i| graph[*i].name == file)?;
+    let target = same_as_target(graph, target);
+    let g = import_graph(graph, imports);
+
+    // For every dependent, the import edge that leads one step closer
+    let mut next: HashMap<NodeIndex, EdgeIndex> = HashMap::new();
+    let mut depth: HashMap<NodeIndex, usize> = HashMap::from([(target, 0)]);
+    let mut queue = VecDeque::from([target]);
+    while let Some(idx) = queue.pop_front() {
+        let d = depth[&idx];
+        if max_depth.is_some_and(|max| d >= max) {
+            continue;
+        }
+        let mut importers: Vec<_> = g.edges_directed(idx, Direction::Incoming).collect();
+        importers.sort_by(|a, b| g[a.source()].name.cmp(&g[b.source()].name));
+        for edge in importers {
+            if depth.contains_key(&edge.source()) {
+                continue;
+            }
+            depth.insert(edge.source(), d + 1);
+            next.insert(edge.source(), edge.id());
+            queue.push_back(edge.source());
+        }
+    }
+
+    let mut found: Vec<Dependent> = next
+        .keys()
+        .map(|&idx| {
+            let mut chain = Vec::new();
+            let mut cur = idx;
+            while let Some(&edge) = next.get(&cur) {
+                chain.push(Hop::new(&g, edge));
+                cur = g.edge_endpoints(edge).unwrap().1;
+            }
+            Dependent {
+                file: g[idx].name.clone(),
+                depth: depth[&idx],
+                chain,
+            }
+        })
+        .collect();
+    found.sort_by(|a, b| (a.depth, &a.file).cmp(&(b.depth, &b.file)));
+    Some(found)
+}
+
+/// Whether node `name` is what `target` asks for: the node itself, anything
+/// below it (`lodash` matches `lodash/fp`, a folder matches its files), or a
+/// builtin with or without the `node:` prefix.
+pub(crate) fn matches_target<'a>(name: &'a str, target: &'a str) -> bool {
+    let target = target.trim_end_matches('/');
+    let bare = |s: &'a str| s.strip_prefix("node:").unwrap_or(s);
+    bare(name) == bare(target) || name.strip_prefix(target).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Explain why `to` ends up in the graph: the shortest import chain to each
+/// node matching `to`, starting from any file matching one of the `from`
+/// glob patterns. Chains are sorted by length, then by the node they reach.
+pub fn import_paths(
+    graph: &DiGraph<Node, EdgeData>,
+    from: &[String],
+    to: &str,
+    imports: ImportFilter,
+) -> Vec<Vec<Hop>> {
+    let g = import_graph(graph, imports);
+    let mut sources: Vec<NodeIndex> = g
+        .node_indices()
+        .filter(|i| !is_type_node(&g[*i]) && matches_entrypoint(&g[*i].name, from))
+        .collect();
+    sources.sort_by(|a, b| g[*a].name.cmp(&g[*b].name));
+    let targets: HashSet<NodeIndex> = g
+        .node_indices()
+        .filter(|i| !is_type_node(&g[*i]) && matches_target(&g[*i].name, to))
+        .map(|i| same_as_target(graph, i))
+        .collect();
+
+    // Breadth-first from every source at once, remembering how each node
+    // was first reached
+    let mut prev: HashMap<NodeIndex, EdgeIndex> = HashMap::new();
+    let mut seen: HashSet<NodeIndex> = sources.iter().copied().collect();
+    let mut queue: VecDeque<NodeIndex> = sources.into();
+    let mut reached = Vec::new();
+    while let Some(idx) = queue.pop_front() {
+        let mut edges: Vec<_> = g.edges(idx).collect();
+        edges.sort_by(|a, b| g[a.target()].name.cmp(&g[b.target()].name));
+        for edge in edges {
+            if !seen.insert(edge.target()) {
+                continue;
+            }
+            prev.insert(edge.target(), edge.id());
+            queue.push_back(edge.target());
+            if targets.contains(&edge.target()) {
+                reached.push(edge.target());
+            }
+        }
+    }
+
+    let mut paths: Vec<Vec<Hop>> = reached
+        .into_iter()
+        .map(|idx| {
+            let mut path = Vec::new();
+            let mut cur = idx;
+            while let Some(&edge) = prev.get(&cur) {
+                path.push(Hop::new(&g, edge));
+                cur = g.edge_endpoints(edge).unwrap().0;
+            }
+            path.reverse();
+            path
+        })
+        .collect();
+    paths.sort_by(|a, b| (a.len(), &a[a.len() - 1].to).cmp(&(b.len(), &b[b.len() - 1].to)));
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::EdgeType;
+    use dep_core::test_util::import;
+
+    fn import_at(kind: ImportKind, file: &str, line: usize) -> EdgeData {
+        EdgeData {
+            location: Some(Location {
+                file: file.into(),
+                line,
+                column: 1,
+            }),
+            ..import(kind)
+        }
+    }
+
+    #[test]
+    fn test_dependents_with_depth_limit() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let folder = g.add_node(Node { name: "src".into() });
+        let util = g.add_node(Node { name: "src/util.ts".into() });
+        let page = g.add_node(Node { name: "src/page.ts".into() });
+        let app = g.add_node(Node { name: "app.ts".into() });
+        let types = g.add_node(Node { name: "types.ts".into() });
+        g.add_edge(folder, util, EdgeType::Regular.into());
+        g.add_edge(folder, page, EdgeType::Regular.into());
+        g.add_edge(page, util, import_at(ImportKind::Static, "src/page.ts", 1));
+        g.add_edge(app, page, import_at(ImportKind::Dynamic, "app.ts", 3));
+        g.add_edge(types, util, import_at(ImportKind::Type, "types.ts", 2));
+
+        let all = dependents(&g, "src/util.ts", None, ImportFilter::All).unwrap();
+        let files: Vec<_> = all.iter().map(|d| (d.file.as_str(), d.depth)).collect();
+        assert_eq!(files, [("src/page.ts", 1), ("types.ts", 1), ("app.ts", 2)]);
+        let chain: Vec<_> = all[2].chain.iter().map(|h| h.to_string()).collect();
+        assert_eq!(chain, ["app.ts:3:1 -> src/page.ts", "src/page.ts:1:1 -> src/util.ts"]);
+
+        let direct = dependents(&g, "src/util.ts", Some(1), ImportFilter::Runtime).unwrap();
+        assert_eq!(direct.len(), 1);
+        assert_eq!(direct[0].file, "src/page.ts");
+
+        assert!(dependents(&g, "missing.ts", None, ImportFilter::All).is_none());
+    }
+
+    #[test]
+    fn test_import_paths_to_package_and_builtin() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let main = g.add_node(Node { name: "main.ts".into() });
+        let a = g.add_node(Node { name: "a.ts".into() });
+        let b = g.add_node(Node { name: "b.ts".into() });
+        let lodash = g.add_node(Node { name: "lodash".into() });
+        let fp = g.add_node(Node { name: "lodash/fp".into() });
+        let fs = g.add_node(Node { name: "node:fs".into() });
+        g.add_edge(main, a, import_at(ImportKind::Static, "main.ts", 1));
+        g.add_edge(main, b, import_at(ImportKind::Static, "main.ts", 2));
+        g.add_edge(a, fp, import_at(ImportKind::Static, "a.ts", 1));
+        g.add_edge(b, a, import_at(ImportKind::Static, "b.ts", 1));
+        g.add_edge(b, lodash, import_at(ImportKind::Require, "b.ts", 4));
+        g.add_edge(b, fs, import_at(ImportKind::Static, "b.ts", 5));
+
+        let entry = ["main.*".to_string()];
+        let paths = import_paths(&g, &entry, "lodash", ImportFilter::All);
+        let rendered: Vec<Vec<String>> = paths
+            .iter()
+            .map(|p| p.iter().map(|h| h.to_string()).collect())
+            .collect();
+        assert_eq!(
+            rendered,
+            [
+                ["main.ts:2:1 -> b.ts", "b.ts:4:1 -> lodash"],
+                ["main.ts:1:1 -> a.ts", "a.ts:1:1 -> lodash/fp"],
+            ]
+        );
+
+        let paths = import_paths(&g, &entry, "fs", ImportFilter::All);
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0][1].to, "node:fs");
+
+        assert!(import_paths(&g, &["a.ts".to_string()], "node:fs", ImportFilter::All).is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+use petgraph::graph::DiGraph;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use dep_core::{EdgeData, EdgeType, Node, NodeKind, resolve_node_kind};
+
+use crate::paths::{Hop, matches_target};
+use crate::{ImportFilter, import_graph, matches_entrypoint};
+
+/// A dependency rule declared under `[[rules]]` in `dep.toml`.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Rule {
+    /// Shown next to violations
+    pub name: Option<String>,
+    /// Files or packages the rule applies to
+    pub from: Vec<String>,
+    /// Modules they must not depend on
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Exceptions to `deny`. Without `deny`, the only modules they may
+    /// depend on
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+impl Rule {
+    /// Whether depending on `target` breaks the rule.
+    fn forbids(&self, target: &str) -> bool {
+        let allowed = matches_any(target, &self.allow);
+        if self.deny.is_empty() {
+            !allowed
+        } else {
+            matches_any(target, &self.deny) && !allowed
+        }
+    }
+}
+
+/// Patterns are globs, and also match everything below the node they name,
+/// so `lodash` covers `lodash/fp` and `src/server` covers its files.
+fn matches_any(name: &str, patterns: &[String]) -> bool {
+    matches_entrypoint(name, patterns) || patterns.iter().any(|p| matches_target(name, p))
+}
+
+/// A dependency that breaks a rule.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    /// Name of the broken rule, or its position in the config
+    pub rule: String,
+    pub import: Hop,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.rule, self.import)
+    }
+}
+
+/// Evaluate rules against the imports selected by `imports` and the
+/// dependencies declared by packages. Violations are sorted by rule, then
+/// by location.
+pub fn check_rules(
+    graph: &DiGraph<Node, EdgeData>,
+    rules: &[Rule],
+    imports: ImportFilter,
+) -> Vec<Violation> {
+    let g = import_graph(graph, imports);
+    let mut edges: Vec<Hop> = g.edge_indices().map(|e| Hop::new(&g, e)).collect();
+    for edge in graph.edge_references() {
+        if edge.weight().kind != EdgeType::Regular
+            || edge.weight().import_kind.is_some()
+            || resolve_node_kind(graph, edge.source()) != NodeKind::Package
+            || !matches!(
+                resolve_node_kind(graph, edge.target()),
+                NodeKind::Package | NodeKind::External
+            )
+        {
+            continue;
+        }
+        edges.push(Hop::new(graph, edge.id()));
+    }
+
+    let mut violations = Vec::new();
+    for (i, rule) in rules.iter().enumerate() {
+        let label = rule.name.clone().unwrap_or_else(|| format!("#{}", i + 1));
+        let mut broken: Vec<&Hop> = edges
+            .iter()
+            .filter(|hop| matches_any(&hop.from, &rule.from) && rule.forbids(&hop.to))
+            .collect();
+        broken.sort_by(|a, b| {
+            let line = |h: &Hop| h.location.as_ref().map(|l| (l.line, l.column));
+            (&a.from, line(a), &a.to).cmp(&(&b.from, line(b), &b.to))
+        });
+        violations.extend(broken.into_iter().map(|hop| Violation {
+            rule: label.clone(),
+            import: hop.clone(),
+        }));
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::ImportKind;
+    use dep_core::test_util::import;
+
+    fn rule(from: &str, deny: &[&str], allow: &[&str]) -> Rule {
+        Rule {
+            name: None,
+            from: vec![from.into()],
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_deny_and_allow_rules() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let pkg_type = g.add_node(Node { name: NodeKind::Package.type_node_name().into() });
+        let ext_type = g.add_node(Node { name: NodeKind::External.type_node_name().into() });
+        let button = g.add_node(Node { name: "src/ui/button.ts".into() });
+        let db = g.add_node(Node { name: "src/server/db.ts".into() });
+        let types = g.add_node(Node { name: "src/server/types.ts".into() });
+        let domain = g.add_node(Node { name: "src/domain/user.ts".into() });
+        let zod = g.add_node(Node { name: "zod".into() });
+        let app = g.add_node(Node { name: "@acme/app".into() });
+        let legacy = g.add_node(Node { name: "@internal/legacy".into() });
+        g.add_edge(app, pkg_type, EdgeType::TypeOf.into());
+        g.add_edge(legacy, ext_type, EdgeType::TypeOf.into());
+        g.add_edge(app, legacy, EdgeType::Regular.into());
+        g.add_edge(button, db, import(ImportKind::Static));
+        g.add_edge(button, types, import(ImportKind::Type));
+        g.add_edge(domain, zod, import(ImportKind::Static));
+        g.add_edge(domain, button, import(ImportKind::Static));
+
+        let rules = [
+            rule("src/ui/**", &["src/server"], &["src/server/types.ts"]),
+            rule("src/domain/**", &[], &["src/domain/**", "zod"]),
+            rule("*", &["@internal/legacy"], &[]),
+        ];
+        let found: Vec<_> = check_rules(&g, &rules, ImportFilter::All)
+            .iter()
+            .map(|v| format!("{}: {} -> {}", v.rule, v.import.from, v.import.to))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "#1: src/ui/button.ts -> src/server/db.ts",
+                "#2: src/domain/user.ts -> src/ui/button.ts",
+                "#3: @acme/app -> @internal/legacy",
+            ]
+        );
+    }
+}
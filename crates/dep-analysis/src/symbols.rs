@@ -0,0 +1,384 @@
+use petgraph::graph::{DiGraph, EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use dep_core::{EdgeData, EdgeType, Location, Node, NodeKind};
+use dep_core::{is_type_node, resolve_node_kind};
+
+/// Names exported by a module, mapped to the symbol nodes that declare them.
+/// A name re-exported from a module outside the graph maps to no nodes.
+pub type ExportMap = BTreeMap<String, Vec<NodeIndex>>;
+
+/// Resolves the exports of modules by following re-exports, `export *`
+/// chains and barrel folders (`SameAs` edges to an `index` file).
+pub struct ExportResolver<'a> {
+    graph: &'a DiGraph<Node, EdgeData>,
+    cache: HashMap<NodeIndex, ExportMap>,
+    /// Modules being resolved, by their depth in the stack
+    visiting: HashMap<NodeIndex, usize>,
+}
+
+impl<'a> ExportResolver<'a> {
+    pub fn new(graph: &'a DiGraph<Node, EdgeData>) -> Self {
+        Self {
+            graph,
+            cache: HashMap::new(),
+            visiting: HashMap::new(),
+        }
+    }
+
+    /// Return every name `module` exports and where it is declared.
+    pub fn exports(&mut self, module: NodeIndex) -> ExportMap {
+        self.resolve(module).0
+    }
+
+    /// The exports of `module`, and the lowest stack depth of the modules
+    /// being resolved that they were cut short at. A map cut short at a
+    /// module further up lacks that module's names, so only the module
+    /// where a re-export cycle was entered caches its map.
+    fn resolve(&mut self, module: NodeIndex) -> (ExportMap, usize) {
+        if let Some(map) = self.cache.get(&module) {
+            return (map.clone(), usize::MAX);
+        }
+        // Re-export cycles contribute nothing past the first visit
+        if let Some(&depth) = self.visiting.get(&module) {
+            return (ExportMap::new(), depth);
+        }
+        let depth = self.visiting.len();
+        self.visiting.insert(module, depth);
+        let mut low = usize::MAX;
+        let graph = self.graph;
+        let prefix = format!("{}#", graph[module].name);
+        let mut map = ExportMap::new();
+        let mut named = Vec::new();
+        let mut barrels = Vec::new();
+        let mut stars = Vec::new();
+        for edge in graph.edges(module) {
+            let weight = edge.weight();
+            match weight.kind {
+                EdgeType::Export => {
+                    let symbol = &graph[edge.target()].name;
+                    let name = symbol.strip_prefix(&prefix).unwrap_or(symbol);
+                    map.entry(name.to_string())
+                        .or_default()
+                        .push(edge.target());
+                }
+                EdgeType::SameAs => barrels.push(edge.target()),
+                _ if weight.import_kind.is_some_and(|k| k.is_reexport()) => {
+                    for binding in &weight.symbols {
+                        if binding.local == "*" {
+                            stars.push(edge.target());
+                        } else {
+                            named.push((binding, edge.target()));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (binding, target) in named {
+            let (exports, cut) = self.resolve(target);
+            low = low.min(cut);
+            let symbols = if binding.imported == "*" {
+                exports.into_values().flatten().collect()
+            } else {
+                exports.get(&binding.imported).cloned().unwrap_or_default()
+            };
+            map.insert(binding.local.clone(), symbols);
+        }
+        for target in barrels {
+            let (exports, cut) = self.resolve(target);
+            low = low.min(cut);
+            for (name, symbols) in exports {
+                map.entry(name).or_insert(symbols);
+            }
+        }
+        // Explicit exports shadow names from `export *`, which never
+        // re-exports `default`
+        for target in stars {
+            let (exports, cut) = self.resolve(target);
+            low = low.min(cut);
+            for (name, symbols) in exports {
+                if name != "default" {
+                    map.entry(name).or_insert(symbols);
+                }
+            }
+        }
+        self.visiting.remove(&module);
+        if low >= depth {
+            self.cache.insert(module, map.clone());
+        }
+        (map, low)
+    }
+}
+
+/// A module importing an exported symbol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SymbolUse {
+    /// The importing module
+    pub user: NodeIndex,
+    /// The symbol node the imported binding resolves to
+    pub symbol: NodeIndex,
+    /// The import edge the binding flows across
+    pub edge: EdgeIndex,
+}
+
+/// Resolve every imported binding in the graph to the symbols it uses.
+/// Namespace imports (`import * as x`, `require`, `import()`) use every
+/// export of the target. Re-exports only pass symbols through and are not
+/// counted as uses.
+pub fn symbol_uses(graph: &DiGraph<Node, EdgeData>) -> Vec<SymbolUse> {
+    let mut resolver = ExportResolver::new(graph);
+    let mut seen = HashSet::new();
+    let mut uses = Vec::new();
+    for edge in graph.edge_references() {
+        let weight = edge.weight();
+        let Some(kind) = weight.import_kind else {
+            continue;
+        };
+        if kind.is_reexport() || weight.symbols.is_empty() {
+            continue;
+        }
+        let exports = resolver.exports(edge.target());
+        for binding in &weight.symbols {
+            let symbols: Vec<NodeIndex> = if binding.imported == "*" {
+                exports.values().flatten().copied().collect()
+            } else {
+                exports.get(&binding.imported).cloned().unwrap_or_default()
+            };
+            for symbol in symbols {
+                let used = SymbolUse {
+                    user: edge.source(),
+                    symbol,
+                    edge: edge.id(),
+                };
+                if seen.insert(used) {
+                    uses.push(used);
+                }
+            }
+        }
+    }
+    uses
+}
+
+/// Find every module that uses `name` exported from the module `file`,
+/// including imports made through barrel files.
+pub fn symbol_users(graph: &DiGraph<Node, EdgeData>, file: &str, name: &str) -> Vec<SymbolUse> {
+    let Some(module) = graph.node_indices().find(|i| graph[*i].name == file) else {
+        return Vec::new();
+    };
+    let targets = ExportResolver::new(graph)
+        .exports(module)
+        .remove(name)
+        .unwrap_or_default();
+    symbol_uses(graph)
+        .into_iter()
+        .filter(|u| targets.contains(&u.symbol))
+        .collect()
+}
+
+/// An exported symbol that no module in the graph imports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnusedExport {
+    pub file: String,
+    pub name: String,
+    /// Where the symbol is exported
+    pub location: Option<Location>,
+}
+
+/// Whether `name` matches one of the entrypoint glob patterns. Patterns that
+/// are not valid globs are compared literally.
+pub fn matches_entrypoint(name: &str, entrypoints: &[String]) -> bool {
+    entrypoints.iter().any(|p| match glob::Pattern::new(p) {
+        Ok(pattern) => pattern.matches(name),
+        Err(_) => name == p,
+    })
+}
+
+/// List exported symbols that no other module imports, directly or through
+/// re-exports. Everything exported by a module matching `entrypoints` (glob
+/// patterns), including what it re-exports, is treated as used.
+pub fn unused_exports(graph: &DiGraph<Node, EdgeData>, entrypoints: &[String]) -> Vec<UnusedExport> {
+    let mut used: HashSet<NodeIndex> = symbol_uses(graph).into_iter().map(|u| u.symbol).collect();
+    let mut resolver = ExportResolver::new(graph);
+    for idx in graph.node_indices() {
+        let node = &graph[idx];
+        if is_type_node(node) || resolve_node_kind(graph, idx) == NodeKind::Symbol {
+            continue;
+        }
+        if matches_entrypoint(&node.name, entrypoints) {
+            used.extend(resolver.exports(idx).into_values().flatten());
+        }
+    }
+
+    let mut unused = Vec::new();
+    for edge in graph.edge_references() {
+        if edge.weight().kind != EdgeType::Export || used.contains(&edge.target()) {
+            continue;
+        }
+        let file = graph[edge.source()].name.clone();
+        let symbol = &graph[edge.target()].name;
+        let name = symbol
+            .strip_prefix(&format!("{file}#"))
+            .unwrap_or(symbol)
+            .to_string();
+        unused.push(UnusedExport {
+            file,
+            name,
+            location: edge.weight().location.clone(),
+        });
+    }
+    unused.sort_by(|a, b| {
+        let line = |u: &UnusedExport| u.location.as_ref().map(|l| (l.line, l.column));
+        (&a.file, line(a), &a.name).cmp(&(&b.file, line(b), &b.name))
+    });
+    unused
+}
+
+/// Build a symbol-level graph: modules point to the symbols they export
+/// (`Export` edges) and to the symbols they import, with re-export chains
+/// resolved to the declaring module.
+pub fn symbol_graph(graph: &DiGraph<Node, EdgeData>) -> DiGraph<Node, EdgeData> {
+    let mut out: DiGraph<Node, EdgeData> = DiGraph::new();
+    let mut map: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+
+    fn ensure(
+        idx: NodeIndex,
+        graph: &DiGraph<Node, EdgeData>,
+        out: &mut DiGraph<Node, EdgeData>,
+        map: &mut HashMap<NodeIndex, NodeIndex>,
+    ) -> NodeIndex {
+        *map.entry(idx).or_insert_with(|| out.add_node(graph[idx].clone()))
+    }
+
+    for idx in graph.node_indices() {
+        if is_type_node(&graph[idx]) {
+            ensure(idx, graph, &mut out, &mut map);
+        }
+    }
+    for edge in graph.edge_references() {
+        if edge.weight().kind == EdgeType::Export {
+            let s = ensure(edge.source(), graph, &mut out, &mut map);
+            let t = ensure(edge.target(), graph, &mut out, &mut map);
+            out.add_edge(s, t, edge.weight().clone());
+        }
+    }
+    for used in symbol_uses(graph) {
+        let s = ensure(used.user, graph, &mut out, &mut map);
+        let t = ensure(used.symbol, graph, &mut out, &mut map);
+        out.add_edge(s, t, graph[used.edge].clone());
+    }
+    // Keep node kinds for the nodes that made it into the symbol graph
+    for edge in graph.edge_references() {
+        if edge.weight().kind == EdgeType::TypeOf
+            && let (Some(&s), Some(&t)) = (map.get(&edge.source()), map.get(&edge.target()))
+        {
+            out.add_edge(s, t, edge.weight().clone());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::test_util::import;
+    use dep_core::{Binding, ImportKind, NodeKind};
+
+    fn import_with(kind: ImportKind, symbols: Vec<Binding>) -> EdgeData {
+        EdgeData { symbols, ..import(kind) }
+    }
+
+    #[test]
+    fn test_symbol_users_through_renamed_reexport() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let date = g.add_node(Node { name: "date.ts".into() });
+        let format = g.add_node(Node { name: "date.ts#formatDate".into() });
+        let barrel = g.add_node(Node { name: "index.ts".into() });
+        let app = g.add_node(Node { name: "app.ts".into() });
+        let other = g.add_node(Node { name: "other.ts".into() });
+        g.add_edge(date, format, EdgeType::Export.into());
+        g.add_edge(
+            barrel,
+            date,
+            import_with(ImportKind::ReExport, vec![Binding::new("formatDate", "fmt")]),
+        );
+        g.add_edge(
+            app,
+            barrel,
+            import_with(ImportKind::Static, vec![Binding::new("fmt", "f")]),
+        );
+        g.add_edge(
+            other,
+            barrel,
+            import_with(ImportKind::Static, vec![Binding::new("formatDate", "f")]),
+        );
+
+        let users = symbol_users(&g, "date.ts", "formatDate");
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].user, app);
+        assert_eq!(users[0].symbol, format);
+    }
+
+    #[test]
+    fn test_unused_exports_honours_entrypoints() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let symbol_type = g.add_node(Node { name: NodeKind::Symbol.type_node_name().into() });
+        let lib = g.add_node(Node { name: "lib.ts".into() });
+        let used = g.add_node(Node { name: "lib.ts#used".into() });
+        let unused = g.add_node(Node { name: "lib.ts#unused".into() });
+        let public = g.add_node(Node { name: "lib.ts#public".into() });
+        let main = g.add_node(Node { name: "main.ts".into() });
+        let entry = g.add_node(Node { name: "entry.ts".into() });
+        for s in [used, unused, public] {
+            g.add_edge(lib, s, EdgeType::Export.into());
+            g.add_edge(s, symbol_type, EdgeType::TypeOf.into());
+        }
+        g.add_edge(
+            main,
+            lib,
+            import_with(ImportKind::Static, vec![Binding::new("used", "used")]),
+        );
+        g.add_edge(
+            entry,
+            lib,
+            import_with(ImportKind::ReExport, vec![Binding::new("public", "public")]),
+        );
+
+        let names = |entries: &[String]| {
+            unused_exports(&g, entries)
+                .into_iter()
+                .map(|u| u.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&[]), ["public", "unused"]);
+        assert_eq!(names(&["entry.*".to_string()]), ["unused"]);
+    }
+
+    #[test]
+    fn test_exports_through_reexport_cycle() {
+        let mut g: DiGraph<Node, EdgeData> = DiGraph::new();
+        let symbol_type = g.add_node(Node { name: NodeKind::Symbol.type_node_name().into() });
+        let a = g.add_node(Node { name: "a.ts".into() });
+        let x = g.add_node(Node { name: "a.ts#x".into() });
+        let b = g.add_node(Node { name: "b.ts".into() });
+        let y = g.add_node(Node { name: "b.ts#y".into() });
+        let main = g.add_node(Node { name: "main.ts".into() });
+        for (module, s) in [(a, x), (b, y)] {
+            g.add_edge(module, s, EdgeType::Export.into());
+            g.add_edge(s, symbol_type, EdgeType::TypeOf.into());
+        }
+        g.add_edge(a, b, import_with(ImportKind::ReExport, vec![Binding::namespace()]));
+        g.add_edge(b, a, import_with(ImportKind::ReExport, vec![Binding::namespace()]));
+        // `a` is resolved first, which enters the cycle through it
+        g.add_edge(main, a, import_with(ImportKind::Static, vec![Binding::new("y", "y")]));
+        g.add_edge(main, b, import_with(ImportKind::Static, vec![Binding::new("x", "x")]));
+
+        let mut resolver = ExportResolver::new(&g);
+        for module in [a, b] {
+            let names: Vec<_> = resolver.exports(module).into_keys().collect();
+            assert_eq!(names, ["x", "y"]);
+        }
+        assert!(unused_exports(&g, &[]).is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use petgraph::graph::DiGraph;
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+use std::fmt;
+
+use dep_core::{EdgeData, Node, NodeKind, resolve_node_kind};
+
+use crate::paths::Hop;
+use crate::{ImportFilter, import_graph};
+
+/// An import whose specifier does not resolve to a file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UnresolvedImport {
+    /// The specifier as written in the import
+    pub specifier: String,
+    pub import: Hop,
+}
+
+impl fmt::Display for UnresolvedImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: cannot resolve '{}'",
+            self.import.location.as_ref().map_or(self.import.from.clone(), |l| l.to_string()),
+            self.specifier
+        )
+    }
+}
+
+/// Find imports, among those selected by `imports`, that point to an
+/// unresolved node. Sorted by importing file, then line.
+pub fn unresolved_imports(graph: &DiGraph<Node, EdgeData>, imports: ImportFilter) -> Vec<UnresolvedImport> {
+    let g = import_graph(graph, imports);
+    let mut unresolved: Vec<UnresolvedImport> = g
+        .edge_references()
+        .filter(|e| resolve_node_kind(graph, e.target()) == NodeKind::Unresolved)
+        .map(|e| UnresolvedImport {
+            specifier: e.weight().specifier.clone().unwrap_or_else(|| g[e.target()].name.clone()),
+            import: Hop::new(&g, e.id()),
+        })
+        .collect();
+    unresolved.sort_by(|a, b| {
+        let line = |u: &UnresolvedImport| u.import.location.as_ref().map(|l| (l.line, l.column));
+        (&a.import.from, line(a)).cmp(&(&b.import.from, line(b)))
+    });
+    unresolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::{ImportKind, Location, new_graph_ctx};
+    use dep_core::test_util::import;
+    use dep_core::{attach_type, ensure_node};
+
+    #[test]
+    fn test_unresolved_imports() {
+        let mut data = new_graph_ctx();
+        let a = ensure_node("src/a.ts", &mut data);
+        let b = ensure_node("src/b.ts", &mut data);
+        let missing = ensure_node("src/missing", &mut data);
+        attach_type(missing, NodeKind::Unresolved, &mut data);
+        let edge = |location: Option<Location>, spec: &str| EdgeData {
+            location,
+            specifier: Some(spec.into()),
+            ..import(ImportKind::Static)
+        };
+        let line = |line: usize| Some(Location { file: "src/a.ts".into(), line, column: 1 });
+        data.graph.add_edge(a, missing, edge(line(3), "./missing"));
+        data.graph.add_edge(a, b, edge(line(1), "./b"));
+        data.graph.add_edge(b, missing, edge(None, "./missing"));
+
+        let unresolved = unresolved_imports(&data.graph, ImportFilter::All);
+        let found: Vec<_> = unresolved.iter().map(|u| u.to_string()).collect();
+        assert_eq!(
+            found,
+            ["src/a.ts:3:1: cannot resolve './missing'", "src/b.ts: cannot resolve './missing'"]
+        );
+    }
+}
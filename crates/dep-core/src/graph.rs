@@ -24,7 +24,7 @@ pub fn attach_type(node_idx: NodeIndex, kind: NodeKind, data: &mut GraphCtx) {
     if let Some(&type_idx) = data.type_nodes.get(&kind) {
         // Only add the edge if it doesn't already exist
         if data.graph.find_edge(node_idx, type_idx).is_none() {
-            data.graph.add_edge(node_idx, type_idx, EdgeType::TypeOf);
+            data.graph.add_edge(node_idx, type_idx, EdgeType::TypeOf.into());
         }
     }
 }
@@ -48,7 +48,7 @@ pub fn ensure_folders(
             let idx = ensure_node(&accum, data);
             attach_type(idx, NodeKind::Folder, data);
             if data.graph.find_edge(parent_idx, idx).is_none() {
-                data.graph.add_edge(parent_idx, idx, EdgeType::Regular);
+                data.graph.add_edge(parent_idx, idx, EdgeType::Regular.into());
             }
             parent_idx = idx;
         }
@@ -1,7 +1,7 @@
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 
-use crate::{EdgeType, Node, NodeKind};
+use crate::{EdgeData, EdgeType, Node, NodeKind};
 
 /// Check if a node is a type singleton node
 pub fn is_type_node(node: &Node) -> bool {
@@ -9,12 +9,12 @@ pub fn is_type_node(node: &Node) -> bool {
 }
 
 /// Resolve the NodeKind for a node by looking at its TypeOf edges.
-pub fn resolve_node_kind(graph: &DiGraph<Node, EdgeType>, idx: NodeIndex) -> NodeKind {
+pub fn resolve_node_kind(graph: &DiGraph<Node, EdgeData>, idx: NodeIndex) -> NodeKind {
     let mut best_kind = NodeKind::File;
     let mut best_precedence = 0u8;
 
     for edge in graph.edges(idx) {
-        if *edge.weight() == EdgeType::TypeOf {
+        if edge.weight().kind == EdgeType::TypeOf {
             let target = &graph[edge.target()];
             for kind in NodeKind::type_node_variants() {
                 if target.name == kind.type_node_name() {
@@ -0,0 +1,238 @@
+use jsonc_parser::{ParseOptions, parse_to_serde_value};
+use serde_json::Value;
+use vfs::VfsPath;
+
+/// Specifier keys and the addresses they map to, in declaration order.
+pub type SpecifierMap = Vec<(String, String)>;
+
+/// An import map, from a `<script type="importmap">` in an HTML page, an
+/// `importmap.json`, or the `imports` and `scopes` of a `deno.json`.
+#[derive(Clone, Debug)]
+pub struct ImportMap {
+    /// The file declaring the map
+    pub path: VfsPath,
+    /// Folder of that file. Relative addresses and scopes resolve against
+    /// it, and the map applies to the files below it that have no closer map
+    pub dir: VfsPath,
+    pub imports: SpecifierMap,
+    /// `scopes`: a URL prefix and the specifier map used by modules under it
+    pub scopes: Vec<(String, SpecifierMap)>,
+}
+
+fn specifier_map(value: Option<&Value>) -> SpecifierMap {
+    let Some(Value::Object(map)) = value else {
+        return Vec::new();
+    };
+    map.iter()
+        .filter_map(|(key, address)| Some((key.clone(), address.as_str()?.to_string())))
+        .collect()
+}
+
+/// Look `spec` up in a specifier map: an exact key, otherwise the longest key
+/// ending in `/` that prefixes it, with the rest of `spec` appended to its
+/// address.
+fn lookup(map: &SpecifierMap, spec: &str) -> Option<String> {
+    if let Some((_, address)) = map.iter().find(|(key, _)| key == spec) {
+        return Some(address.clone());
+    }
+    map.iter()
+        .filter(|(key, address)| key.ends_with('/') && address.ends_with('/') && spec.starts_with(key.as_str()))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(key, address)| format!("{address}{}", &spec[key.len()..]))
+}
+
+impl ImportMap {
+    /// Read the `imports` and `scopes` of a parsed import map or `deno.json`
+    /// declared by the file at `path`.
+    pub fn from_json(path: &VfsPath, json: &Value) -> Self {
+        let scopes = match json.get("scopes") {
+            Some(Value::Object(scopes)) => scopes
+                .iter()
+                .map(|(prefix, map)| (prefix.clone(), specifier_map(Some(map))))
+                .collect(),
+            _ => Vec::new(),
+        };
+        ImportMap {
+            path: path.clone(),
+            dir: path.parent(),
+            imports: specifier_map(json.get("imports")),
+            scopes,
+        }
+    }
+
+    /// Load an `importmap.json`, `deno.json` or `deno.jsonc`, allowing
+    /// comments and trailing commas as Deno does. A Deno config naming a
+    /// separate file in `importMap` loads that file instead.
+    pub fn load(path: &VfsPath) -> anyhow::Result<Self> {
+        let read = |path: &VfsPath| -> anyhow::Result<Value> {
+            let json = parse_to_serde_value(&path.read_to_string()?, &ParseOptions::default())?;
+            Ok(json.unwrap_or_default())
+        };
+        let json = read(path)?;
+        if matches!(path.filename().as_str(), "deno.json" | "deno.jsonc")
+            && let Some(file) = json.get("importMap").and_then(Value::as_str)
+        {
+            let target = path.parent().join(file)?;
+            return Ok(Self::from_json(&target, &read(&target)?));
+        }
+        Ok(Self::from_json(path, &json))
+    }
+
+    /// Whether the map declares no mappings.
+    pub fn is_empty(&self) -> bool {
+        self.imports.is_empty() && self.scopes.iter().all(|(_, map)| map.is_empty())
+    }
+
+    /// Whether `path` is inside this map's folder.
+    pub fn contains(&self, path: &VfsPath) -> bool {
+        let dir = self.dir.as_str().trim_end_matches('/');
+        dir.is_empty()
+            || path
+                .as_str()
+                .strip_prefix(dir)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// Path prefix a scope applies to. Scopes starting with `/` are relative
+    /// to `root`; remote scopes never contain a project file.
+    fn scope_prefix(&self, root: &VfsPath, scope: &str) -> Option<String> {
+        let base = scope.trim_end_matches('/');
+        let dir = if scope.starts_with('/') {
+            match base.trim_start_matches('/') {
+                "" => root.clone(),
+                abs => root.join(abs).ok()?,
+            }
+        } else if base.is_empty() {
+            self.dir.clone()
+        } else if base.starts_with('.') {
+            self.dir.join(base).ok()?
+        } else {
+            return None;
+        };
+        let mut prefix = dir.as_str().to_string();
+        if scope.ends_with('/') {
+            prefix.push('/');
+        }
+        Some(prefix)
+    }
+
+    /// The address `spec` maps to when imported from `from`: through the
+    /// most specific scope containing `from` that maps it, then through
+    /// `imports`.
+    pub fn resolve(&self, root: &VfsPath, from: &VfsPath, spec: &str) -> Option<String> {
+        let mut scopes: Vec<_> = self
+            .scopes
+            .iter()
+            .filter_map(|(scope, map)| {
+                let prefix = self.scope_prefix(root, scope)?;
+                let applies = if prefix.ends_with('/') {
+                    from.as_str().starts_with(&prefix)
+                } else {
+                    from.as_str() == prefix
+                };
+                applies.then_some((prefix.len(), map))
+            })
+            .collect();
+        scopes.sort_by_key(|(len, _)| usize::MAX - len);
+        scopes
+            .into_iter()
+            .find_map(|(_, map)| lookup(map, spec))
+            .or_else(|| lookup(&self.imports, spec))
+    }
+}
+
+/// The import maps applying to `path`: those in the closest folder
+/// containing it, in their order in `import_maps`.
+///
+/// Browsers apply a page's map to the modules the page loads, wherever they
+/// are. Files are resolved without knowing which pages load them, so this
+/// approximates it by folder: a module outside the page's folder misses
+/// its map, and the pages of one folder share their maps.
+pub fn closest_import_maps<'a>(import_maps: &'a [ImportMap], path: &VfsPath) -> Vec<&'a ImportMap> {
+    let Some(depth) = import_maps
+        .iter()
+        .filter(|m| m.contains(path))
+        .map(|m| m.dir.as_str().len())
+        .max()
+    else {
+        return Vec::new();
+    };
+    import_maps
+        .iter()
+        .filter(|m| m.contains(path) && m.dir.as_str().len() == depth)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_import_map_resolve() {
+        let root = VfsPath::new(vfs::MemoryFS::new());
+        let json = serde_json::json!({
+            "imports": {
+                "lit": "/vendor/lit/index.js",
+                "lit/": "/vendor/lit/",
+                "app/": "./src/",
+                "app/config": "./src/config.prod.js",
+                "preact": "https://esm.sh/preact@10",
+                "broken/": "./not-a-prefix"
+            },
+            "scopes": {
+                "/": { "lodash": "/vendor/lodash-root.js" },
+                "./src/legacy/": { "lit": "/vendor/lit-2/index.js" }
+            }
+        });
+        let map = ImportMap::from_json(&root.join("web/index.html").unwrap(), &json);
+        let from = |path: &str| root.join(path).unwrap();
+        let resolve = |path: &str, spec: &str| map.resolve(&root, &from(path), spec);
+        assert_eq!(resolve("web/main.js", "lit").as_deref(), Some("/vendor/lit/index.js"));
+        assert_eq!(resolve("web/main.js", "lit/decorators.js").as_deref(), Some("/vendor/lit/decorators.js"));
+        assert_eq!(resolve("web/main.js", "app/config").as_deref(), Some("./src/config.prod.js"));
+        assert_eq!(resolve("web/main.js", "app/ui/button.js").as_deref(), Some("./src/ui/button.js"));
+        assert_eq!(resolve("web/main.js", "preact").as_deref(), Some("https://esm.sh/preact@10"));
+        assert_eq!(resolve("web/main.js", "broken/x").as_deref(), None);
+        assert_eq!(resolve("web/src/legacy/old.js", "lit").as_deref(), Some("/vendor/lit-2/index.js"));
+        assert_eq!(resolve("web/src/legacy/old.js", "lodash").as_deref(), Some("/vendor/lodash-root.js"));
+        assert_eq!(resolve("web/main.js", "react"), None);
+    }
+
+    #[test]
+    fn test_load_deno_config() {
+        let root = VfsPath::new(vfs::MemoryFS::new());
+        let write = |path: &str, contents: &str| {
+            let file = root.join(path).unwrap();
+            file.parent().create_dir_all().unwrap();
+            write!(file.create_file().unwrap(), "{contents}").unwrap();
+        };
+        write(
+            "app/deno.jsonc",
+            "{\n  // Shared with the browser build\n  \"imports\": { \"@std/\": \"./vendor/std/\", },\n}",
+        );
+        write("site/deno.json", r#"{ "importMap": "./maps/import_map.json" }"#);
+        write("site/maps/import_map.json", r#"{ "imports": { "preact": "./preact.js" } }"#);
+
+        let map = ImportMap::load(&root.join("app/deno.jsonc").unwrap()).unwrap();
+        assert_eq!(map.imports, [("@std/".to_string(), "./vendor/std/".to_string())]);
+        let map = ImportMap::load(&root.join("site/deno.json").unwrap()).unwrap();
+        assert_eq!(map.path.as_str(), "/site/maps/import_map.json");
+        assert_eq!(map.imports, [("preact".to_string(), "./preact.js".to_string())]);
+    }
+
+    #[test]
+    fn test_closest_import_maps() {
+        let root = VfsPath::new(vfs::MemoryFS::new());
+        let map = |path: &str| ImportMap::from_json(&root.join(path).unwrap(), &serde_json::json!({}));
+        let maps = [map("deno.json"), map("site/index.html"), map("site/about.html")];
+        let names = |path: &str| -> Vec<String> {
+            closest_import_maps(&maps, &root.join(path).unwrap())
+                .iter()
+                .map(|m| m.path.as_str().to_string())
+                .collect()
+        };
+        assert_eq!(names("main.ts"), ["/deno.json"]);
+        assert_eq!(names("site/js/app.js"), ["/site/index.html", "/site/about.html"]);
+    }
+}
@@ -1,8 +1,22 @@
 use std::path::Path;
 use vfs::VfsPath;
 
+use crate::import_map::{ImportMap, closest_import_maps};
+use crate::package_json::{DEFAULT_CONDITIONS, PackageManifest, nearest_package};
+use crate::tsconfig::{TsConfig, closest_tsconfigs};
+
 pub const JS_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs", "mts", "cts"];
 
+/// Extensions of single-file components, whose script blocks are modules.
+/// Tried after [`JS_EXTENSIONS`] for imports written without an extension.
+pub const COMPONENT_EXTENSIONS: &[&str] = &["vue", "svelte", "astro"];
+
+/// Whether a file with extension `ext` is a module, as opposed to an asset
+/// such as a stylesheet or an image.
+pub fn is_module_extension(ext: &str) -> bool {
+    JS_EXTENSIONS.contains(&ext) || COMPONENT_EXTENSIONS.contains(&ext)
+}
+
 pub fn is_node_builtin(name: &str) -> bool {
     let n = name.strip_prefix("node:").unwrap_or(name);
     matches!(
@@ -43,59 +57,366 @@ pub fn is_node_builtin(name: &str) -> bool {
     )
 }
 
+/// TypeScript sources an ESM-style import may name by their output
+/// extension, e.g. `./foo.js` for `foo.ts`, in the order tsc tries them.
+const TS_SOURCE_EXTENSIONS: &[(&str, &[&str])] = &[
+    ("js", &["ts", "tsx", "d.ts"]),
+    ("jsx", &["tsx", "ts", "d.ts"]),
+    ("mjs", &["mts", "d.mts"]),
+    ("cjs", &["cts", "d.cts"]),
+];
+
 pub fn resolve_relative_import(dir: &VfsPath, spec: &str) -> Option<VfsPath> {
-    if let Ok(base) = dir.join(spec) {
-        if base.exists().ok()? {
-            return Some(base);
+    resolve_path(&dir.join(spec).ok()?, &[""])
+}
+
+fn existing(dir: &VfsPath, name: String) -> Option<VfsPath> {
+    dir.join(name).ok().filter(|p| p.is_file().unwrap_or(false))
+}
+
+/// Resolve a path the way an import names it: the file itself, or the
+/// folder when it has an index file, which is left for the `SameAs` edge of
+/// its index file to lead on, then with each JS and component extension
+/// added. A path ending in `.js`, `.jsx`, `.mjs` or `.cjs` that is not on
+/// disk resolves to the TypeScript source it is compiled from. Each of
+/// `suffixes` (the tsconfig `moduleSuffixes`, `""` for none) is tried in
+/// order before the extension, and folders resolve to their suffixed index
+/// file first, as the index parser knows nothing of suffixes.
+fn resolve_path(base: &VfsPath, suffixes: &[&str]) -> Option<VfsPath> {
+    let parent = base.parent();
+    let name = base.filename();
+    let is_file = base.is_file().unwrap_or(false);
+    let has_index = || JS_EXTENSIONS.iter().any(|ext| existing(base, format!("index.{ext}")).is_some());
+    if suffixes == [""] && (is_file || has_index()) {
+        return Some(base.clone());
+    }
+    match Path::new(&name).extension().and_then(|e| e.to_str()) {
+        Some(ext) => {
+            let stem = &name[..name.len() - ext.len() - 1];
+            let sources = TS_SOURCE_EXTENSIONS
+                .iter()
+                .find(|(js, _)| *js == ext)
+                .map_or(&[][..], |(_, ts)| *ts);
+            for suffix in suffixes {
+                let found = existing(&parent, format!("{stem}{suffix}.{ext}")).or_else(|| {
+                    sources
+                        .iter()
+                        .find_map(|ts| existing(&parent, format!("{stem}{suffix}.{ts}")))
+                });
+                if found.is_some() {
+                    return found;
+                }
+            }
+            None
         }
-        let p = Path::new(spec);
-        if p.extension().is_none() {
-            for ext in JS_EXTENSIONS {
-                if let Ok(candidate) = dir.join(format!("{spec}.{}", ext))
-                    && candidate.exists().ok()? {
-                        return Some(candidate);
-                    }
+        None => {
+            for suffix in suffixes {
+                if let Some(found) = JS_EXTENSIONS
+                    .iter()
+                    .chain(COMPONENT_EXTENSIONS)
+                    .find_map(|ext| existing(&parent, format!("{name}{suffix}.{ext}")))
+                {
+                    return Some(found);
+                }
             }
-            for ext in JS_EXTENSIONS {
-                if let Ok(candidate) = base.join(format!("index.{}", ext))
-                    && candidate.exists().ok()? {
-                        return Some(candidate);
-                    }
+            if is_file {
+                return Some(base.clone());
+            }
+            for suffix in suffixes {
+                if let Some(found) = JS_EXTENSIONS
+                    .iter()
+                    .find_map(|ext| existing(base, format!("index{suffix}.{ext}")))
+                {
+                    return Some(found);
+                }
+            }
+            None
+        }
+    }
+}
+
+/// The `moduleSuffixes` of the tsconfig closest to `from`, or `[""]`.
+fn module_suffixes<'a>(tsconfigs: &'a [TsConfig], from: &VfsPath) -> Vec<&'a str> {
+    closest_tsconfigs(tsconfigs, from)
+        .into_iter()
+        .find(|t| !t.module_suffixes.is_empty())
+        .map_or_else(|| vec![""], |t| t.module_suffixes.iter().map(String::as_str).collect())
+}
+
+/// Resolve a bare specifier through the `paths` of the tsconfigs closest to
+/// `from`. Every target of the best matching pattern is tried in order, then
+/// those of the next best.
+pub fn resolve_alias_import(tsconfigs: &[TsConfig], from: &VfsPath, spec: &str) -> Option<VfsPath> {
+    let suffixes = module_suffixes(tsconfigs, from);
+    for tsconfig in closest_tsconfigs(tsconfigs, from) {
+        for (mapping, star) in tsconfig.matching_paths(spec) {
+            for target in &mapping.targets {
+                let target = target.replacen('*', star, 1);
+                if let Some(found) = tsconfig
+                    .paths_base
+                    .join(&target)
+                    .ok()
+                    .and_then(|base| resolve_path(&base, &suffixes))
+                {
+                    return Some(found);
+                }
             }
         }
     }
     None
 }
 
-pub fn resolve_alias_import(aliases: &[(String, VfsPath)], spec: &str) -> Option<VfsPath> {
-    for (alias, base) in aliases {
-        if spec == alias || spec.starts_with(&format!("{}/", alias)) {
-            let rest = if spec == alias {
-                ""
-            } else {
-                &spec[alias.len() + 1..]
+/// Resolve a bare specifier through the import maps closest to `from`.
+/// Addresses starting with `/` are relative to `root`, and `./` or `../`
+/// ones to the folder of the map. A specifier mapped to a remote URL, or to
+/// an `npm:` or `jsr:` specifier in Deno, resolves to no file.
+pub fn resolve_import_map(root: &VfsPath, import_maps: &[ImportMap], from: &VfsPath, spec: &str) -> Option<VfsPath> {
+    let map = closest_import_maps(import_maps, from)
+        .into_iter()
+        .find_map(|map| Some((map, map.resolve(root, from, spec)?)));
+    let (map, address) = map?;
+    let address = address.split(['?', '#']).next().unwrap_or_default();
+    if address.starts_with("./") || address.starts_with("../") {
+        resolve_relative_import(&map.dir, address)
+    } else if let Some(abs) = address.strip_prefix('/').filter(|abs| !abs.starts_with('/')) {
+        resolve_relative_import(root, abs)
+    } else {
+        None
+    }
+}
+
+/// Resolve a relative import of `from` with the settings of its closest
+/// tsconfig: its `moduleSuffixes`, and when the import is not on disk next
+/// to `from`, its `rootDirs`, trying the same relative path from each other
+/// root as if all roots were merged into one folder.
+pub fn resolve_ts_relative_import(tsconfigs: &[TsConfig], from: &VfsPath, spec: &str) -> Option<VfsPath> {
+    let suffixes = module_suffixes(tsconfigs, from);
+    let dir = from.parent();
+    if let Some(found) = resolve_path(&dir.join(spec).ok()?, &suffixes) {
+        return Some(found);
+    }
+    for tsconfig in closest_tsconfigs(tsconfigs, from) {
+        let Some(rel) = tsconfig.root_dirs.iter().find_map(|root| {
+            let root = root.as_str().trim_end_matches('/');
+            let rest = dir.as_str().strip_prefix(root)?;
+            (rest.is_empty() || rest.starts_with('/')).then(|| rest.trim_start_matches('/'))
+        }) else {
+            continue;
+        };
+        for root in &tsconfig.root_dirs {
+            let Ok(root_dir) = (if rel.is_empty() { Ok(root.clone()) } else { root.join(rel) }) else {
+                continue;
             };
-            if let Ok(candidate_base) = base.join(rest) {
-                if candidate_base.exists().ok()? {
-                    return Some(candidate_base);
-                }
-                let p = Path::new(rest);
-                if p.extension().is_none() {
-                    for ext in JS_EXTENSIONS {
-                        if let Ok(candidate) = base.join(format!("{rest}.{}", ext))
-                            && candidate.exists().ok()? {
-                                return Some(candidate);
-                            }
-                    }
-                    for ext in JS_EXTENSIONS {
-                        if let Ok(candidate) = candidate_base.join(format!("index.{}", ext))
-                            && candidate.exists().ok()? {
-                                return Some(candidate);
-                            }
-                    }
-                }
+            if root_dir == dir {
+                continue;
+            }
+            if let Some(found) = root_dir.join(spec).ok().and_then(|base| resolve_path(&base, &suffixes)) {
+                return Some(found);
             }
         }
     }
     None
 }
+
+/// Prefix of unresolved node names, so they never merge with the file or
+/// folder node of the same path, e.g. a folder imported without an index.
+pub const UNRESOLVED_PREFIX: &str = "unresolved:";
+
+/// Node name for an import of `spec` from `from` that did not resolve:
+/// [`UNRESOLVED_PREFIX`] followed by the path it points to relative to
+/// `root` for a relative import, otherwise by the specifier itself.
+pub fn unresolved_target(root: &VfsPath, from: &VfsPath, spec: &str) -> String {
+    let target = match from.parent().join(spec) {
+        Ok(target) if spec.starts_with('.') => target
+            .as_str()
+            .strip_prefix(root.as_str().trim_end_matches('/'))
+            .unwrap_or(target.as_str())
+            .trim_start_matches('/')
+            .to_string(),
+        // A bare specifier, or a path above the root
+        _ => spec.to_string(),
+    };
+    format!("{UNRESOLVED_PREFIX}{target}")
+}
+
+/// Options controlling how bare specifiers are resolved.
+#[derive(Clone, Debug)]
+pub struct ResolveOptions {
+    /// Conditions matched in package.json `exports` and `imports` maps
+    pub conditions: Vec<String>,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            conditions: DEFAULT_CONDITIONS.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+}
+
+/// What a specifier resolved to through a package.json.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackageTarget {
+    /// A file inside the package
+    File(VfsPath),
+    /// Another bare specifier, e.g. `"#dep": "lodash"` in `imports`
+    Bare(String),
+}
+
+/// Split a bare specifier into its package name and the subpath below it:
+/// `@scope/pkg/sub` gives `("@scope/pkg", "./sub")`, `pkg` gives `("pkg", ".")`.
+pub fn split_bare_specifier(spec: &str) -> (&str, String) {
+    let name_end = if spec.starts_with('@') {
+        spec.match_indices('/').nth(1).map(|(i, _)| i)
+    } else {
+        spec.find('/')
+    };
+    match name_end {
+        Some(i) => (&spec[..i], format!(".{}", &spec[i..])),
+        None => (spec, ".".to_string()),
+    }
+}
+
+/// Whether a URL is fetched from another server: `http:`, `https:` or
+/// protocol-relative.
+pub fn is_remote(url: &str) -> bool {
+    let lower = url.get(..8).unwrap_or(url).to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://") || url.starts_with("//")
+}
+
+/// Resolve `#name` imports through the `imports` field of the package
+/// containing `from`, and bare imports of packages in the walked tree
+/// (workspace packages, or the importing package by its own name) to their
+/// source files.
+pub fn resolve_package_import(
+    packages: &[PackageManifest],
+    from: &VfsPath,
+    spec: &str,
+    conditions: &[String],
+) -> Option<PackageTarget> {
+    if spec.starts_with('#') {
+        let package = nearest_package(packages, from)?;
+        let target = package.resolve_imports(spec, conditions)?;
+        return if target.starts_with("./") {
+            resolve_relative_import(&package.dir, &target).map(PackageTarget::File)
+        } else {
+            Some(PackageTarget::Bare(target))
+        };
+    }
+    let (name, subpath) = split_bare_specifier(spec);
+    let own = nearest_package(packages, from).filter(|p| p.name.as_deref() == Some(name));
+    let package = own.or_else(|| packages.iter().find(|p| p.name.as_deref() == Some(name)))?;
+    resolve_package_subpath(package, &subpath, conditions).map(PackageTarget::File)
+}
+
+/// Resolve a subpath of a local package to a file: through `exports` when
+/// the package has them, otherwise (or when the export target is not on
+/// disk, e.g. an unbuilt `dist` folder) through `module`, `main` and `types`
+/// for the package root and plain relative lookup for deeper subpaths.
+fn resolve_package_subpath(
+    package: &PackageManifest,
+    subpath: &str,
+    conditions: &[String],
+) -> Option<VfsPath> {
+    if package.exports.is_some() {
+        let exported = package
+            .resolve_exports(subpath, conditions)
+            .and_then(|target| resolve_relative_import(&package.dir, &target));
+        if exported.is_some() {
+            return exported;
+        }
+    }
+    if subpath != "." {
+        return resolve_relative_import(&package.dir, subpath);
+    }
+    [&package.module, &package.main, &package.types]
+        .into_iter()
+        .flatten()
+        .find_map(|entry| {
+            let entry = format!("./{}", entry.trim_start_matches("./"));
+            resolve_relative_import(&package.dir, &entry).filter(|p| p.is_file().unwrap_or(false))
+        })
+        .or_else(|| resolve_relative_import(&package.dir, "./index"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(paths: &[&str]) -> VfsPath {
+        let root: VfsPath = vfs::MemoryFS::new().into();
+        for path in paths {
+            let p = root.join(path).unwrap();
+            p.parent().create_dir_all().unwrap();
+            p.create_file().unwrap();
+        }
+        root
+    }
+
+    fn resolved(found: Option<VfsPath>) -> Option<String> {
+        found.map(|p| p.as_str().trim_start_matches('/').to_string())
+    }
+
+    #[test]
+    fn test_ts_source_for_js_extension() {
+        let root = files(&["src/a.ts", "src/b.tsx", "src/c.mts", "src/d.cts", "src/e.js", "src/e.ts", "src/f.d.ts", "src/ui/index.ts"]);
+        let dir = root.join("src").unwrap();
+        let resolve = |spec: &str| resolved(resolve_relative_import(&dir, spec));
+        assert_eq!(resolve("./a.js").as_deref(), Some("src/a.ts"));
+        assert_eq!(resolve("./b.js").as_deref(), Some("src/b.tsx"));
+        assert_eq!(resolve("./b.jsx").as_deref(), Some("src/b.tsx"));
+        assert_eq!(resolve("./c.mjs").as_deref(), Some("src/c.mts"));
+        assert_eq!(resolve("./d.cjs").as_deref(), Some("src/d.cts"));
+        // A file on disk wins over the source it may be compiled from
+        assert_eq!(resolve("./e.js").as_deref(), Some("src/e.js"));
+        assert_eq!(resolve("./f.js").as_deref(), Some("src/f.d.ts"));
+        assert_eq!(resolve("./a.ts").as_deref(), Some("src/a.ts"));
+        assert_eq!(resolve("./a.mjs"), None);
+        // A folder resolves to itself, its index file being linked to it,
+        // unless it has no index file
+        assert_eq!(resolve("./ui").as_deref(), Some("src/ui"));
+        assert_eq!(resolve("../src"), None);
+    }
+
+    #[test]
+    fn test_module_suffixes() {
+        let root = files(&["app/button.ios.tsx", "app/button.tsx", "app/list.tsx", "app/theme/index.native.ts"]);
+        let tsconfig = TsConfig {
+            path: root.join("tsconfig.json").unwrap(),
+            dir: root.clone(),
+            paths_base: root.clone(),
+            paths: Vec::new(),
+            root_dirs: Vec::new(),
+            module_suffixes: vec![".ios".into(), ".native".into(), "".into()],
+        };
+        let from = root.join("app/main.ts").unwrap();
+        let resolve = |spec: &str| resolved(resolve_ts_relative_import(std::slice::from_ref(&tsconfig), &from, spec));
+        assert_eq!(resolve("./button").as_deref(), Some("app/button.ios.tsx"));
+        assert_eq!(resolve("./button.js").as_deref(), Some("app/button.ios.tsx"));
+        assert_eq!(resolve("./list").as_deref(), Some("app/list.tsx"));
+        assert_eq!(resolve("./theme").as_deref(), Some("app/theme/index.native.ts"));
+    }
+
+    #[test]
+    fn test_resolve_import_map() {
+        let root = files(&["vendor/lit/index.js", "site/js/app.ts", "site/js/ui/button.js"]);
+        let json = serde_json::json!({
+            "imports": {
+                "lit": "/vendor/lit/index.js",
+                "app/": "./js/",
+                "preact": "https://esm.sh/preact@10"
+            }
+        });
+        let import_maps = [ImportMap::from_json(&root.join("site/index.html").unwrap(), &json)];
+        let resolve = |from: &str, spec: &str| {
+            resolved(resolve_import_map(&root, &import_maps, &root.join(from).unwrap(), spec))
+        };
+        assert_eq!(resolve("site/js/app.ts", "lit").as_deref(), Some("vendor/lit/index.js"));
+        assert_eq!(resolve("site/js/app.ts", "app/ui/button.js").as_deref(), Some("site/js/ui/button.js"));
+        // `.js` addresses find the TypeScript source like relative imports do
+        assert_eq!(resolve("site/index.html", "app/app.js").as_deref(), Some("site/js/app.ts"));
+        assert_eq!(resolve("site/js/app.ts", "preact"), None);
+        // The map only applies below the page's folder
+        assert_eq!(resolve("other/main.ts", "lit"), None);
+    }
+}
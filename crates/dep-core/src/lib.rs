@@ -3,16 +3,19 @@ pub mod graph;
 pub mod graph_util;
 pub mod logger;
 pub mod js_resolve;
+pub mod import_map;
+pub mod package_json;
+pub mod tsconfig;
 #[cfg(feature = "testutil")]
 pub mod test_util;
 
 pub use logger::{ConsoleLogger, EmptyLogger, LogLevel, Logger};
-pub use types::{Context, Edge, GraphCtx, Parser};
+pub use types::{Context, Edge, GraphCtx, Location, Parser};
 pub use graph::{attach_type, ensure_folders, ensure_node};
 pub use graph_util::{is_type_node, resolve_node_kind};
 
 use petgraph::graph::DiGraph;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Node types used for categorization and rendering.
 /// These become singleton nodes in the graph that regular nodes point to.
@@ -25,6 +28,12 @@ pub enum NodeKind {
     Folder,
     Asset,
     Package,
+    /// A name exported by a module, named `<file>#<export>`
+    Symbol,
+    /// An import that does not resolve: a relative path with no file behind
+    /// it, named by the path it points to, or a `#name` missing from the
+    /// package's `imports`
+    Unresolved,
 }
 
 impl NodeKind {
@@ -36,6 +45,8 @@ impl NodeKind {
             NodeKind::Builtin,
             NodeKind::External,
             NodeKind::Asset,
+            NodeKind::Symbol,
+            NodeKind::Unresolved,
         ]
     }
 
@@ -48,6 +59,8 @@ impl NodeKind {
             NodeKind::Folder => "__type__::folder",
             NodeKind::Asset => "__type__::asset",
             NodeKind::Package => "__type__::package",
+            NodeKind::Symbol => "__type__::symbol",
+            NodeKind::Unresolved => "__type__::unresolved",
         }
     }
 
@@ -55,11 +68,13 @@ impl NodeKind {
     pub fn precedence(&self) -> u8 {
         match self {
             NodeKind::File => 0,
-            NodeKind::Asset => 1,
-            NodeKind::External => 2,
-            NodeKind::Builtin => 3,
-            NodeKind::Folder => 4,
-            NodeKind::Package => 5,
+            NodeKind::Unresolved => 1,
+            NodeKind::Asset => 2,
+            NodeKind::External => 3,
+            NodeKind::Builtin => 4,
+            NodeKind::Folder => 5,
+            NodeKind::Package => 6,
+            NodeKind::Symbol => 7,
         }
     }
 }
@@ -73,6 +88,8 @@ impl std::fmt::Display for NodeKind {
             NodeKind::Folder => "folder",
             NodeKind::Asset => "asset",
             NodeKind::Package => "package",
+            NodeKind::Symbol => "symbol",
+            NodeKind::Unresolved => "unresolved",
         };
         write!(f, "{}", name)
     }
@@ -84,12 +101,206 @@ pub struct Node {
     pub name: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize)]
 pub enum EdgeType {
+    #[default]
     Regular,
     SameAs,
     /// Edge from a node to its type singleton node
     TypeOf,
+    /// Edge from a module to a symbol node it exports
+    Export,
+    /// Edge from a package to a version of a dependency installed by a
+    /// lockfile, e.g. `lodash` -> `lodash@4.17.21`
+    Resolved,
+}
+
+/// How a dependency was introduced in the source of the importing file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum ImportKind {
+    /// `import x from '...'` or `import { x } from '...'`
+    Static,
+    /// `import '...'` without any bindings
+    SideEffect,
+    /// `export ... from '...'` or `export * from '...'`
+    ReExport,
+    /// `require('...')` at any depth, or `import x = require('...')`
+    Require,
+    /// `require.resolve('...')`
+    RequireResolve,
+    /// `import('...')`, loaded lazily at runtime
+    Dynamic,
+    /// Type-only dependency with no runtime effect: `import type`,
+    /// `import('...')` in a type position, or an import whose specifiers are
+    /// all `type`
+    Type,
+    /// Type-only re-export: `export type ... from '...'`
+    TypeReExport,
+    /// `<script src="...">` or `<link rel="modulepreload">` in an HTML page
+    Script,
+    /// File matched by `import.meta.glob(...)`
+    Glob,
+    /// Stylesheet loaded by `@import`, `@use` or `@forward`, a CSS Modules
+    /// `composes: ... from '...'`, or `<link rel="stylesheet">` in an HTML page
+    Style,
+    /// Font, image or other file referenced by `url(...)` in a stylesheet, or
+    /// by an HTML page: icons, the manifest, `<img>`, `<source>` and posters
+    Url,
+}
+
+impl ImportKind {
+    /// Whether the dependency only exists at the type level.
+    pub fn is_type_only(&self) -> bool {
+        matches!(self, ImportKind::Type | ImportKind::TypeReExport)
+    }
+
+    /// Whether the importing module re-exports the bindings it imports.
+    pub fn is_reexport(&self) -> bool {
+        matches!(self, ImportKind::ReExport | ImportKind::TypeReExport)
+    }
+
+    /// Whether the dependency is loaded lazily (a code-split boundary) rather
+    /// than when the importing module is evaluated.
+    pub fn is_lazy(&self) -> bool {
+        matches!(self, ImportKind::Dynamic)
+    }
+}
+
+impl std::fmt::Display for ImportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ImportKind::Static => "static",
+            ImportKind::SideEffect => "side-effect",
+            ImportKind::ReExport => "re-export",
+            ImportKind::Require => "require",
+            ImportKind::RequireResolve => "require-resolve",
+            ImportKind::Dynamic => "dynamic",
+            ImportKind::Type => "type",
+            ImportKind::TypeReExport => "type-re-export",
+            ImportKind::Script => "script",
+            ImportKind::Glob => "glob",
+            ImportKind::Style => "style",
+            ImportKind::Url => "url",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Which field of a `package.json` declares a dependency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    /// `dependencies`, installed with the package in production
+    Prod,
+    /// `devDependencies`, only needed to build and test the package
+    Dev,
+    /// `peerDependencies`, provided by the consumer of the package
+    Peer,
+    /// `optionalDependencies`, installed when available
+    Optional,
+}
+
+impl DependencyKind {
+    /// The `package.json` field listing dependencies of this kind.
+    pub fn field(&self) -> &'static str {
+        match self {
+            DependencyKind::Prod => "dependencies",
+            DependencyKind::Dev => "devDependencies",
+            DependencyKind::Peer => "peerDependencies",
+            DependencyKind::Optional => "optionalDependencies",
+        }
+    }
+}
+
+impl std::fmt::Display for DependencyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            DependencyKind::Prod => "prod",
+            DependencyKind::Dev => "dev",
+            DependencyKind::Peer => "peer",
+            DependencyKind::Optional => "optional",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for DependencyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prod" => Ok(DependencyKind::Prod),
+            "dev" => Ok(DependencyKind::Dev),
+            "peer" => Ok(DependencyKind::Peer),
+            "optional" => Ok(DependencyKind::Optional),
+            _ => Err(format!("unknown dependency kind `{s}`, expected prod, dev, peer or optional")),
+        }
+    }
+}
+
+/// A dependency declared in a `package.json`, carried by the edge from the
+/// package to the dependency.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Dependency {
+    pub kind: DependencyKind,
+    /// Declared version range, e.g. `^1.2.0` or `workspace:*`
+    pub version: String,
+}
+
+/// A binding that flows across an import edge.
+///
+/// `imported` is the name in the target module (`default` for default
+/// imports, `*` for the whole namespace). `local` is the name in the importing
+/// module, or the exported name for re-exports; `export * from` uses `*` for
+/// both.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Binding {
+    pub imported: String,
+    pub local: String,
+}
+
+impl Binding {
+    pub fn new(imported: impl Into<String>, local: impl Into<String>) -> Self {
+        Binding {
+            imported: imported.into(),
+            local: local.into(),
+        }
+    }
+
+    /// Binding for an import that consumes the whole module namespace.
+    pub fn namespace() -> Self {
+        Binding::new("*", "*")
+    }
+}
+
+/// Weight stored on every edge of the dependency graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct EdgeData {
+    pub kind: EdgeType,
+    /// How the dependency was imported, if the edge comes from an import
+    pub import_kind: Option<ImportKind>,
+    /// Where in the importing file the dependency was declared
+    pub location: Option<Location>,
+    /// Bindings consumed across an import edge
+    pub symbols: Vec<Binding>,
+    /// Kind and version range of a package dependency edge
+    pub dependency: Option<Dependency>,
+    /// The import specifier as written in the source, e.g. `../utils`
+    pub specifier: Option<String>,
+}
+
+impl From<EdgeType> for EdgeData {
+    fn from(kind: EdgeType) -> Self {
+        EdgeData {
+            kind,
+            ..Default::default()
+        }
+    }
+}
+
+/// Canonical name of the symbol node for `name` exported from `file`.
+pub fn symbol_node_name(file: &str, name: &str) -> String {
+    format!("{file}#{name}")
 }
 
 /// Initialize type singleton nodes in a GraphCtx.
@@ -0,0 +1,227 @@
+use serde::Deserialize;
+use serde_json::Value;
+use vfs::VfsPath;
+
+/// Conditions matched in `exports` and `imports` maps unless configured
+/// otherwise. `default` always matches.
+pub const DEFAULT_CONDITIONS: &[&str] = &["import", "require", "node"];
+
+#[derive(Deserialize)]
+struct RawManifest {
+    name: Option<String>,
+    main: Option<String>,
+    module: Option<String>,
+    types: Option<String>,
+    exports: Option<Value>,
+    imports: Option<Value>,
+}
+
+/// The fields of a `package.json` used to resolve imports.
+#[derive(Clone, Debug)]
+pub struct PackageManifest {
+    /// Folder containing the `package.json`
+    pub dir: VfsPath,
+    pub name: Option<String>,
+    pub main: Option<String>,
+    pub module: Option<String>,
+    pub types: Option<String>,
+    pub exports: Option<Value>,
+    pub imports: Option<Value>,
+}
+
+impl PackageManifest {
+    /// Read a `package.json` file. Returns `None` when it is not valid JSON.
+    pub fn load(path: &VfsPath) -> anyhow::Result<Option<Self>> {
+        let content = path.read_to_string()?;
+        let Ok(raw) = serde_json::from_str::<RawManifest>(&content) else {
+            return Ok(None);
+        };
+        Ok(Some(PackageManifest {
+            dir: path.parent(),
+            name: raw.name,
+            main: raw.main,
+            module: raw.module,
+            types: raw.types,
+            exports: raw.exports,
+            imports: raw.imports,
+        }))
+    }
+
+    /// Whether `path` is inside this package's folder.
+    pub fn contains(&self, path: &VfsPath) -> bool {
+        let dir = self.dir.as_str().trim_end_matches('/');
+        dir.is_empty()
+            || path
+                .as_str()
+                .strip_prefix(dir)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// Resolve a subpath (`.` or `./feature`) through the `exports` field.
+    /// Returns the target relative to the package folder.
+    pub fn resolve_exports(&self, subpath: &str, conditions: &[String]) -> Option<String> {
+        let exports = self.exports.as_ref()?;
+        let has_subpaths = exports
+            .as_object()
+            .is_some_and(|map| map.keys().any(|k| k.starts_with('.')));
+        if has_subpaths {
+            resolve_map(exports, subpath, conditions)
+        } else if subpath == "." {
+            // `"exports": "./index.js"` or a conditions object is sugar for
+            // `{ ".": ... }`
+            resolve_target(exports, None, conditions)
+        } else {
+            None
+        }
+    }
+
+    /// Resolve a `#name` specifier through the `imports` field. The target is
+    /// either relative to the package folder or a bare specifier.
+    pub fn resolve_imports(&self, spec: &str, conditions: &[String]) -> Option<String> {
+        resolve_map(self.imports.as_ref()?, spec, conditions)
+    }
+}
+
+/// Find the package that contains `path`: the one with the deepest folder.
+pub fn nearest_package<'a>(packages: &'a [PackageManifest], path: &VfsPath) -> Option<&'a PackageManifest> {
+    packages
+        .iter()
+        .filter(|p| p.contains(path))
+        .max_by_key(|p| p.dir.as_str().len())
+}
+
+/// Look up `key` in an `exports` or `imports` map: an exact key first, then
+/// the pattern with the longest prefix before its `*`.
+fn resolve_map(map: &Value, key: &str, conditions: &[String]) -> Option<String> {
+    let map = map.as_object()?;
+    if let Some(target) = map.get(key)
+        && !key.contains('*')
+    {
+        return resolve_target(target, None, conditions);
+    }
+    let mut best: Option<(&str, &str, &Value)> = None;
+    for (pattern, target) in map {
+        let Some((prefix, suffix)) = pattern.split_once('*') else {
+            continue;
+        };
+        if key.len() < prefix.len() + suffix.len()
+            || !key.starts_with(prefix)
+            || !key.ends_with(suffix)
+        {
+            continue;
+        }
+        if best.is_none_or(|(p, _, _)| prefix.len() > p.len()) {
+            best = Some((prefix, suffix, target));
+        }
+    }
+    let (prefix, suffix, target) = best?;
+    let star = &key[prefix.len()..key.len() - suffix.len()];
+    resolve_target(target, Some(star), conditions)
+}
+
+/// Resolve an export target: a string, the first resolvable entry of an
+/// array, or the first matching condition of an object. `null` excludes the
+/// subpath, even when a later condition matches too.
+fn resolve_target(target: &Value, star: Option<&str>, conditions: &[String]) -> Option<String> {
+    match_target(target, star, conditions).flatten()
+}
+
+/// Like [`resolve_target`], but tells a target excluding the subpath,
+/// `Some(None)`, from one where no condition matches, `None`. Only the
+/// latter moves on to the next entry or condition.
+fn match_target(target: &Value, star: Option<&str>, conditions: &[String]) -> Option<Option<String>> {
+    match target {
+        Value::String(s) => Some(Some(match star {
+            Some(star) => s.replace('*', star),
+            None => s.clone(),
+        })),
+        Value::Null => Some(None),
+        Value::Array(items) => items.iter().find_map(|t| match_target(t, star, conditions)),
+        Value::Object(map) => map
+            .iter()
+            .filter(|(cond, _)| *cond == "default" || conditions.contains(cond))
+            .find_map(|(_, t)| match_target(t, star, conditions)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(json: &str) -> PackageManifest {
+        let raw: RawManifest = serde_json::from_str(json).unwrap();
+        PackageManifest {
+            dir: vfs::MemoryFS::new().into(),
+            name: raw.name,
+            main: raw.main,
+            module: raw.module,
+            types: raw.types,
+            exports: raw.exports,
+            imports: raw.imports,
+        }
+    }
+
+    fn conditions(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resolve_exports_with_conditions() {
+        let pkg = manifest(
+            r#"{"name":"pkg","exports":{
+                ".":{"types":"./index.d.ts","browser":"./browser.js","import":"./index.mjs","require":"./index.cjs"},
+                "./utils/*":{"development":"./src/utils/*.ts","default":"./dist/utils/*.js"},
+                "./utils/internal/*":null,
+                "./package.json":"./package.json"}}"#,
+        );
+        let node = conditions(DEFAULT_CONDITIONS);
+        assert_eq!(pkg.resolve_exports(".", &node).as_deref(), Some("./index.mjs"));
+        assert_eq!(
+            pkg.resolve_exports(".", &conditions(&["browser", "import"])).as_deref(),
+            Some("./browser.js")
+        );
+        assert_eq!(pkg.resolve_exports(".", &conditions(&["require"])).as_deref(), Some("./index.cjs"));
+        assert_eq!(
+            pkg.resolve_exports("./utils/date", &node).as_deref(),
+            Some("./dist/utils/date.js")
+        );
+        assert_eq!(
+            pkg.resolve_exports("./utils/date", &conditions(&["development"])).as_deref(),
+            Some("./src/utils/date.ts")
+        );
+        assert_eq!(pkg.resolve_exports("./utils/internal/x", &node), None);
+        assert_eq!(pkg.resolve_exports("./missing", &node), None);
+
+        let sugar = manifest(r#"{"exports":{"import":"./a.mjs","default":"./a.js"}}"#);
+        assert_eq!(sugar.resolve_exports(".", &conditions(&["require"])).as_deref(), Some("./a.js"));
+        assert_eq!(sugar.resolve_exports("./a", &node), None);
+    }
+
+    #[test]
+    fn test_null_condition_excludes_target() {
+        let pkg = manifest(
+            r#"{"name":"pkg","exports":{
+                ".":{"browser":null,"default":"./x.js"},
+                "./a":{"node":{"import":"./a.mjs"},"default":"./a.js"}}}"#,
+        );
+        assert_eq!(pkg.resolve_exports(".", &conditions(&["browser"])), None);
+        assert_eq!(pkg.resolve_exports(".", &conditions(&["node"])).as_deref(), Some("./x.js"));
+        // A condition whose own conditions all fail to match is skipped
+        assert_eq!(
+            pkg.resolve_exports("./a", &conditions(&["node", "require"])).as_deref(),
+            Some("./a.js")
+        );
+    }
+
+    #[test]
+    fn test_resolve_imports() {
+        let pkg = manifest(r##"{"imports":{"#db":{"node":"./src/db.node.ts","default":"./src/db.ts"},"#lib/*":"./src/lib/*","#dep":"lodash"}}"##);
+        let node = conditions(DEFAULT_CONDITIONS);
+        assert_eq!(pkg.resolve_imports("#db", &node).as_deref(), Some("./src/db.node.ts"));
+        assert_eq!(pkg.resolve_imports("#db", &[]).as_deref(), Some("./src/db.ts"));
+        assert_eq!(pkg.resolve_imports("#lib/a/b.ts", &node).as_deref(), Some("./src/lib/a/b.ts"));
+        assert_eq!(pkg.resolve_imports("#dep", &node).as_deref(), Some("lodash"));
+        assert_eq!(pkg.resolve_imports("#nope", &node), None);
+    }
+}
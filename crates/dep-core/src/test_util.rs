@@ -28,3 +28,25 @@ impl TestFS {
         self.root.clone()
     }
 }
+
+/// A context resolving from `root` with no tsconfigs, packages, conditions
+/// or import maps, which logs nothing.
+pub fn test_context(root: &vfs::VfsPath) -> crate::Context<'_> {
+    crate::Context {
+        root,
+        tsconfigs: &[],
+        packages: &[],
+        conditions: &[],
+        import_maps: &[],
+        logger: &crate::EmptyLogger,
+    }
+}
+
+/// The weight of an import edge of the given kind, with no location,
+/// bindings or specifier.
+pub fn import(kind: crate::ImportKind) -> crate::EdgeData {
+    crate::EdgeData {
+        import_kind: Some(kind),
+        ..crate::EdgeType::Regular.into()
+    }
+}
@@ -0,0 +1,171 @@
+use vfs::VfsPath;
+
+/// A `paths` entry of a tsconfig: a pattern with at most one `*`, and the
+/// targets tried in order, relative to [`TsConfig::paths_base`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathMapping {
+    pub pattern: String,
+    pub targets: Vec<String>,
+}
+
+impl PathMapping {
+    /// Match `spec` against the pattern. Returns the text matched by `*`, or
+    /// an empty string for a pattern without one.
+    pub fn matches<'a>(&self, spec: &'a str) -> Option<&'a str> {
+        match self.pattern.split_once('*') {
+            None => (self.pattern == spec).then_some(""),
+            Some((prefix, suffix)) => {
+                if spec.len() < prefix.len() + suffix.len()
+                    || !spec.starts_with(prefix)
+                    || !spec.ends_with(suffix)
+                {
+                    return None;
+                }
+                Some(&spec[prefix.len()..spec.len() - suffix.len()])
+            }
+        }
+    }
+}
+
+/// The module resolution options of a `tsconfig.json`, with its `extends`
+/// chain applied.
+#[derive(Clone, Debug)]
+pub struct TsConfig {
+    /// The tsconfig file itself
+    pub path: VfsPath,
+    /// Folder of the tsconfig. It applies to the files below it that have no
+    /// closer tsconfig
+    pub dir: VfsPath,
+    /// Folder `paths` targets are relative to: `baseUrl` when set, otherwise
+    /// the folder of the config that declares `paths`
+    pub paths_base: VfsPath,
+    pub paths: Vec<PathMapping>,
+    /// `rootDirs`: folders merged into one virtual folder for relative imports
+    pub root_dirs: Vec<VfsPath>,
+    /// `moduleSuffixes` tried before the extension of an imported file, e.g.
+    /// `[".ios", ""]`. Empty when not set
+    pub module_suffixes: Vec<String>,
+}
+
+impl TsConfig {
+    /// Whether `path` is inside this tsconfig's folder.
+    pub fn contains(&self, path: &VfsPath) -> bool {
+        let dir = self.dir.as_str().trim_end_matches('/');
+        dir.is_empty()
+            || path
+                .as_str()
+                .strip_prefix(dir)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// The `paths` entries matching `spec` with the text matched by `*`, best
+    /// first: exact patterns, then by the length of the prefix before `*`.
+    pub fn matching_paths<'a>(&self, spec: &'a str) -> Vec<(&PathMapping, &'a str)> {
+        let mut matches: Vec<_> = self
+            .paths
+            .iter()
+            .filter_map(|m| Some((m, m.matches(spec)?)))
+            .collect();
+        matches.sort_by_key(|(m, _)| match m.pattern.split_once('*') {
+            None => (0, 0),
+            Some((prefix, _)) => (1, usize::MAX - prefix.len()),
+        });
+        matches
+    }
+}
+
+/// The tsconfigs applying to `path`: those in the closest folder containing
+/// it. A folder can hold several, e.g. a `tsconfig.json` and the
+/// `tsconfig.app.json` it references; they keep their order in `tsconfigs`.
+pub fn closest_tsconfigs<'a>(tsconfigs: &'a [TsConfig], path: &VfsPath) -> Vec<&'a TsConfig> {
+    let Some(depth) = tsconfigs
+        .iter()
+        .filter(|t| t.contains(path))
+        .map(|t| t.dir.as_str().len())
+        .max()
+    else {
+        return Vec::new();
+    };
+    tsconfigs
+        .iter()
+        .filter(|t| t.contains(path) && t.dir.as_str().len() == depth)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(pattern: &str) -> PathMapping {
+        PathMapping {
+            pattern: pattern.to_string(),
+            targets: vec!["src/*".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_path_mapping_matches() {
+        assert_eq!(mapping("@app/*").matches("@app/ui/button"), Some("ui/button"));
+        assert_eq!(mapping("@app/*").matches("@app"), None);
+        assert_eq!(mapping("@app/*/utils").matches("@app/core/utils"), Some("core"));
+        assert_eq!(mapping("@app/*/utils").matches("@app/core/helpers"), None);
+        assert_eq!(mapping("*.css").matches("theme.css"), Some("theme"));
+        assert_eq!(mapping("config").matches("config"), Some(""));
+        assert_eq!(mapping("config").matches("config/dev"), None);
+    }
+
+    #[test]
+    fn test_matching_paths_order() {
+        let root = VfsPath::new(vfs::MemoryFS::new());
+        let tsconfig = TsConfig {
+            path: root.join("tsconfig.json").unwrap(),
+            dir: root.clone(),
+            paths_base: root.clone(),
+            paths: vec![mapping("*"), mapping("@app/*"), mapping("@app/ui/*"), mapping("@app/ui/button")],
+            root_dirs: Vec::new(),
+            module_suffixes: Vec::new(),
+        };
+        let patterns: Vec<_> = tsconfig
+            .matching_paths("@app/ui/button")
+            .iter()
+            .map(|(m, star)| (m.pattern.as_str(), *star))
+            .collect();
+        assert_eq!(
+            patterns,
+            [("@app/ui/button", ""), ("@app/ui/*", "button"), ("@app/*", "ui/button"), ("*", "@app/ui/button")]
+        );
+    }
+
+    #[test]
+    fn test_closest_tsconfigs() {
+        let root = VfsPath::new(vfs::MemoryFS::new());
+        let tsconfig = |dir: &str, name: &str| {
+            let dir = if dir.is_empty() { root.clone() } else { root.join(dir).unwrap() };
+            TsConfig {
+                path: dir.join(name).unwrap(),
+                dir: dir.clone(),
+                paths_base: dir,
+                paths: Vec::new(),
+                root_dirs: Vec::new(),
+                module_suffixes: Vec::new(),
+            }
+        };
+        let tsconfigs = [
+            tsconfig("", "tsconfig.json"),
+            tsconfig("packages/app", "tsconfig.json"),
+            tsconfig("packages/app", "tsconfig.app.json"),
+        ];
+        let names = |path: &str| -> Vec<String> {
+            closest_tsconfigs(&tsconfigs, &root.join(path).unwrap())
+                .iter()
+                .map(|t| t.path.as_str().to_string())
+                .collect()
+        };
+        assert_eq!(names("scripts/build.ts"), ["/tsconfig.json"]);
+        assert_eq!(
+            names("packages/app/src/main.ts"),
+            ["/packages/app/tsconfig.json", "/packages/app/tsconfig.app.json"]
+        );
+        assert_eq!(names("packages/application/main.ts"), ["/tsconfig.json"]);
+    }
+}
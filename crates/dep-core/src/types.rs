@@ -1,12 +1,16 @@
 use petgraph::graph::{DiGraph, NodeIndex};
+use serde::Serialize;
 use std::collections::HashMap;
 use vfs::VfsPath;
 
-use crate::{EdgeType, Logger, Node, NodeKind};
+use crate::import_map::ImportMap;
+use crate::package_json::PackageManifest;
+use crate::tsconfig::TsConfig;
+use crate::{Binding, Dependency, EdgeData, EdgeType, ImportKind, Logger, Node, NodeKind};
 
 #[derive(Debug)]
 pub struct GraphCtx {
-    pub graph: DiGraph<Node, EdgeType>,
+    pub graph: DiGraph<Node, EdgeData>,
     /// Maps canonical node names to their graph indices
     pub nodes: HashMap<String, NodeIndex>,
     /// Maps NodeKind variants to their singleton type node indices
@@ -15,11 +19,47 @@ pub struct GraphCtx {
 
 pub struct Context<'a> {
     pub root: &'a VfsPath,
-    pub aliases: &'a [(String, VfsPath)],
+    /// Every tsconfig found in the walked tree, with `extends` applied
+    pub tsconfigs: &'a [TsConfig],
+    /// Every package.json found in the walked tree. In a monorepo, only the
+    /// workspace root and its declared members
+    pub packages: &'a [PackageManifest],
+    /// Conditions matched in package.json `exports` and `imports` maps
+    pub conditions: &'a [String],
+    /// Import maps from HTML pages, `importmap.json` and `deno.json` files
+    pub import_maps: &'a [ImportMap],
     pub logger: &'a dyn Logger,
 }
 
-#[derive(Clone, Debug)]
+/// Position of an import in a source file. Line and column are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// Compute the location of byte `offset` within `src`.
+    pub fn from_offset(file: &str, src: &str, offset: usize) -> Self {
+        let offset = offset.min(src.len());
+        let before = src.get(..offset).unwrap_or(src);
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Location {
+            file: file.to_string(),
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct Edge {
     pub from: String,
     pub to: String,
@@ -28,10 +68,27 @@ pub struct Edge {
     pub from_type: Option<NodeKind>,
     /// Optional type to attach to the 'to' node (creates a TypeOf edge)
     pub to_type: Option<NodeKind>,
+    /// How the dependency was imported, if the edge comes from an import
+    pub import_kind: Option<ImportKind>,
+    /// Where in the source file the dependency was declared
+    pub location: Option<Location>,
+    /// Bindings consumed across an import edge
+    pub symbols: Vec<Binding>,
+    /// Kind and version range of a package dependency edge
+    pub dependency: Option<Dependency>,
+    /// The import specifier as written in the source, e.g. `../utils`
+    pub specifier: Option<String>,
 }
 
 pub trait Parser: Send + Sync {
     fn name(&self) -> &'static str;
     fn can_parse(&self, path: &VfsPath) -> bool;
     fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>>;
+
+    /// Kind of the node of every file this parser claims, whether or not
+    /// the file has edges. `None` leaves it to the edges, a plain file by
+    /// default.
+    fn file_kind(&self, _path: &VfsPath) -> Option<NodeKind> {
+        None
+    }
 }
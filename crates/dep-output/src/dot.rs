@@ -1,7 +1,7 @@
 use petgraph::graph::DiGraph;
 use petgraph::visit::EdgeRef;
 
-use dep_core::{Node, NodeKind, EdgeType};
+use dep_core::{DependencyKind, EdgeData, EdgeType, Node, NodeKind};
 use dep_core::{is_type_node, resolve_node_kind};
 
 fn node_attrs(kind: &NodeKind) -> (&'static str, Option<&'static str>) {
@@ -12,6 +12,8 @@ fn node_attrs(kind: &NodeKind) -> (&'static str, Option<&'static str>) {
         NodeKind::Folder => ("folder", Some("lightgrey")),
         NodeKind::Asset => ("note", Some("yellow")),
         NodeKind::Package => ("box3d", Some("orange")),
+        NodeKind::Symbol => ("plaintext", None),
+        NodeKind::Unresolved => ("octagon", Some("salmon")),
     }
 }
 
@@ -20,7 +22,7 @@ fn escape_label(s: &str) -> String {
 }
 
 /// Convert a dependency graph to Graphviz dot format.
-pub fn graph_to_dot(graph: &DiGraph<Node, EdgeType>) -> String {
+pub fn graph_to_dot(graph: &DiGraph<Node, EdgeData>) -> String {
     let mut out = String::from("digraph {\n");
     for i in graph.node_indices() {
         let node = &graph[i];
@@ -42,21 +44,48 @@ pub fn graph_to_dot(graph: &DiGraph<Node, EdgeType>) -> String {
         out.push_str("]\n");
     }
     for e in graph.edge_references() {
-        if *e.weight() == EdgeType::TypeOf {
+        if e.weight().kind == EdgeType::TypeOf {
             continue;
         }
         if is_type_node(&graph[e.source()]) || is_type_node(&graph[e.target()]) {
             continue;
         }
-        let style = match e.weight() {
-            EdgeType::SameAs => " [style=dashed]",
-            _ => "",
+        let mut attrs = Vec::new();
+        if e.weight().kind == EdgeType::SameAs {
+            attrs.push("style=dashed".to_string());
+        } else if e.weight().kind == EdgeType::Export {
+            attrs.push("arrowhead=none".to_string());
+        } else if e.weight().kind == EdgeType::Resolved {
+            attrs.push("style=dashed".to_string());
+            attrs.push("arrowhead=empty".to_string());
+        } else if e.weight().import_kind.is_some_and(|k| k.is_lazy()) {
+            attrs.push("style=dotted".to_string());
+        }
+        if let Some(kind) = e.weight().import_kind {
+            attrs.push(format!("label=\"{}\"", kind));
+            if kind.is_type_only() {
+                attrs.push("color=gray".to_string());
+            }
+        }
+        if let Some(dep) = &e.weight().dependency {
+            attrs.push(format!("label=\"{} {}\"", dep.kind, escape_label(&dep.version)));
+            if dep.kind != DependencyKind::Prod {
+                attrs.push("style=dashed".to_string());
+            }
+        }
+        if let Some(loc) = &e.weight().location {
+            attrs.push(format!("tooltip=\"{}\"", escape_label(&loc.to_string())));
+        }
+        let attrs = if attrs.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", attrs.join(", "))
         };
         out.push_str(&format!(
             "    {} -> {}{}\n",
             e.source().index(),
             e.target().index(),
-            style
+            attrs
         ));
     }
     out.push_str("}\n");
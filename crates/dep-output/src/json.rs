@@ -2,7 +2,7 @@ use petgraph::graph::DiGraph;
 use petgraph::visit::EdgeRef;
 use serde::Serialize;
 
-use dep_core::{Node, NodeKind, EdgeType};
+use dep_core::{Binding, Dependency, EdgeData, EdgeType, ImportKind, Location, Node, NodeKind};
 use dep_core::{is_type_node, resolve_node_kind};
 
 #[derive(Serialize)]
@@ -17,6 +17,16 @@ struct JsonEdge {
     to: usize,
     #[serde(rename = "type")]
     kind: EdgeType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    import_kind: Option<ImportKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    symbols: Vec<Binding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependency: Option<Dependency>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    specifier: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -26,7 +36,7 @@ struct JsonGraph {
 }
 
 /// Convert a dependency graph to JSON format.
-pub fn graph_to_json(graph: &DiGraph<Node, EdgeType>) -> String {
+pub fn graph_to_json(graph: &DiGraph<Node, EdgeData>) -> String {
     use std::collections::HashMap;
 
     let mut index_map: HashMap<usize, usize> = HashMap::new();
@@ -48,7 +58,7 @@ pub fn graph_to_json(graph: &DiGraph<Node, EdgeType>) -> String {
     let edges: Vec<JsonEdge> = graph
         .edge_references()
         .filter(|e| {
-            if *e.weight() == EdgeType::TypeOf {
+            if e.weight().kind == EdgeType::TypeOf {
                 return false;
             }
             let src = &graph[e.source()];
@@ -61,7 +71,12 @@ pub fn graph_to_json(graph: &DiGraph<Node, EdgeType>) -> String {
             Some(JsonEdge {
                 from: *from,
                 to: *to,
-                kind: e.weight().clone(),
+                kind: e.weight().kind.clone(),
+                import_kind: e.weight().import_kind,
+                location: e.weight().location.clone(),
+                symbols: e.weight().symbols.clone(),
+                dependency: e.weight().dependency.clone(),
+                specifier: e.weight().specifier.clone(),
             })
         })
         .collect();
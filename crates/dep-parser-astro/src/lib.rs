@@ -0,0 +1,143 @@
+use std::ops::Range;
+use std::path::Path;
+use swc_common::{BytePos, FileName, Span, SyntaxContext};
+use vfs::VfsPath;
+
+use dep_core::{Context, Edge, ImportKind, Parser};
+use dep_parser_js::{BlockTag, Import, ModuleInfo, blank_except, blocks, module_edges, parse_source};
+
+/// Byte range of the component script between the `---` fences at the top
+/// of an Astro component, and the offset where the template starts.
+fn frontmatter(src: &str) -> Option<(Range<usize>, usize)> {
+    let start = src.len() - src.trim_start().len();
+    let rest = src[start..].strip_prefix("---")?;
+    let content_start = src.len() - rest.len();
+    let mut offset = content_start;
+    for line in rest.split_inclusive('\n') {
+        if offset > content_start && line.trim_end() == "---" {
+            return Some((content_start..offset, offset + line.len()));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Parse the frontmatter of an Astro component and the client scripts
+/// Astro bundles. `<script is:inline>` and non-module script types are left
+/// to the browser and skipped.
+pub fn script_info(src: &str, path: &VfsPath) -> anyhow::Result<ModuleInfo> {
+    let file = || FileName::Custom(path.as_str().into());
+    let mut info = ModuleInfo::default();
+    let mut template_start = 0;
+    if let Some((content, end)) = frontmatter(src) {
+        info = parse_source(&blank_except(src, content), "ts", file())?;
+        template_start = end;
+    }
+    for script in blocks(src, BlockTag::Script, template_start) {
+        if script.has_attr("is:inline") {
+            continue;
+        }
+        if let Some((kind, _)) = script.attr("type")
+            && kind != "module"
+            && kind != "text/javascript"
+        {
+            continue;
+        }
+        if let Some((spec, offset)) = script.attr("src") {
+            info.imports.push(Import {
+                specifier: spec.clone(),
+                kind: ImportKind::Script,
+                span: Span::new(
+                    BytePos(*offset as u32),
+                    BytePos((offset + spec.len()) as u32),
+                    SyntaxContext::empty(),
+                ),
+                bindings: Vec::new(),
+            });
+            continue;
+        }
+        let script_info = parse_source(&blank_except(src, script.content), "ts", file())?;
+        info.imports.extend(script_info.imports);
+    }
+    Ok(info)
+}
+
+/// Parses Astro components: the imports of the `---` frontmatter and of the
+/// `<script>` tags in the template.
+pub struct AstroParser;
+
+impl Parser for AstroParser {
+    fn name(&self) -> &'static str {
+        "astro"
+    }
+
+    fn can_parse(&self, path: &VfsPath) -> bool {
+        Path::new(path.as_str())
+            .extension()
+            .and_then(|s| s.to_str())
+            == Some("astro")
+    }
+
+    fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
+        let src = path.read_to_string()?;
+        let info = script_info(&src, path)?;
+        Ok(module_edges(path, &src, info, ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::test_util::{TestFS, test_context};
+
+    #[test]
+    fn test_frontmatter() {
+        let src = "\n---\nimport A from './a';\n---\n<A />";
+        let (content, end) = frontmatter(src).unwrap();
+        assert_eq!(&src[content], "\nimport A from './a';\n");
+        assert_eq!(&src[end..], "<A />");
+        assert_eq!(frontmatter("<p>---</p>"), None);
+        assert_eq!(frontmatter("---\nunterminated"), None);
+    }
+
+    #[test]
+    fn test_astro_edges() {
+        let page = r#"---
+import Layout from '../layouts/Layout.astro';
+import { getPosts } from '../lib/posts';
+const posts = await getPosts();
+const html = "<script src='./not-a-script.ts'></script>";
+---
+<Layout>
+  {posts.map((p) => <a href={p.url}>{p.title}</a>)}
+</Layout>
+<script>
+  import { track } from '../lib/analytics';
+  track();
+</script>
+<script src="../lib/menu.ts"></script>
+<script is:inline src="/vendor/legacy.js"></script>
+<script type="application/ld+json">{"@context": "https://schema.org"}</script>
+"#;
+        let fs = TestFS::new([
+            ("pages/index.astro", page),
+            ("layouts/Layout.astro", ""),
+            ("lib/posts.ts", ""),
+            ("lib/analytics.ts", ""),
+            ("lib/menu.ts", ""),
+        ]);
+        let root = fs.root();
+        let ctx = test_context(&root);
+        let edges = AstroParser.parse(&root.join("pages/index.astro").unwrap(), &ctx).unwrap();
+        let edges: Vec<_> = edges.iter().map(|e| (e.to.as_str(), e.import_kind)).collect();
+        assert_eq!(
+            edges,
+            [
+                ("layouts/Layout.astro", Some(ImportKind::Static)),
+                ("lib/posts.ts", Some(ImportKind::Static)),
+                ("lib/analytics.ts", Some(ImportKind::Static)),
+                ("lib/menu.ts", Some(ImportKind::Script)),
+            ]
+        );
+    }
+}
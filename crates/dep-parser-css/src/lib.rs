@@ -0,0 +1,488 @@
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+use vfs::VfsPath;
+
+use dep_core::js_resolve::{is_module_extension, resolve_alias_import, unresolved_target};
+use dep_core::{Context, Edge, EdgeType, ImportKind, Location, NodeKind, Parser};
+
+/// Stylesheet languages, by file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Syntax {
+    Css,
+    Scss,
+    /// The indented Sass syntax, where statements end at the line break
+    Sass,
+    Less,
+}
+
+impl Syntax {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "css" => Some(Syntax::Css),
+            "scss" => Some(Syntax::Scss),
+            "sass" => Some(Syntax::Sass),
+            "less" => Some(Syntax::Less),
+            _ => None,
+        }
+    }
+
+    /// Extensions tried, in order, for an import written without one.
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Syntax::Css => &[],
+            Syntax::Scss => &["scss", "sass", "css"],
+            Syntax::Sass => &["sass", "scss", "css"],
+            Syntax::Less => &["less", "css"],
+        }
+    }
+
+    /// Whether `//` starts a comment.
+    fn line_comments(&self) -> bool {
+        !matches!(self, Syntax::Css)
+    }
+}
+
+/// A reference to another file found in a stylesheet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reference {
+    pub specifier: String,
+    /// [`ImportKind::Style`] or [`ImportKind::Url`]
+    pub kind: ImportKind,
+    /// Byte offset of the specifier in the source
+    pub offset: usize,
+}
+
+/// Blank out comments, keeping byte offsets and line breaks so positions
+/// still point into the original source. Strings and unquoted `url(...)`
+/// are skipped so `//` in `url(https://...)` is not taken for a comment.
+fn blank_comments(src: &str, line_comments: bool) -> String {
+    let bytes = src.as_bytes();
+    let mut out = String::with_capacity(src.len());
+    let mut i = 0;
+    let blank = |out: &mut String, text: &str| {
+        for c in text.chars() {
+            if c == '\n' {
+                out.push('\n');
+            } else {
+                out.extend(std::iter::repeat_n(' ', c.len_utf8()));
+            }
+        }
+    };
+    while i < bytes.len() {
+        let rest = &src[i..];
+        let end = if let Some(comment) = rest.strip_prefix("/*") {
+            let end = comment.find("*/").map_or(rest.len(), |e| e + 4);
+            blank(&mut out, &rest[..end]);
+            i += end;
+            continue;
+        } else if line_comments && rest.starts_with("//") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            blank(&mut out, &rest[..end]);
+            i += end;
+            continue;
+        } else if bytes[i] == b'"' || bytes[i] == b'\'' {
+            string_end(rest).unwrap_or(rest.len())
+        } else if rest.starts_with("url(") {
+            rest.find(')').map_or(rest.len(), |e| e + 1)
+        } else {
+            rest.chars().next().map_or(1, char::len_utf8)
+        };
+        out.push_str(&rest[..end]);
+        i += end;
+    }
+    out
+}
+
+/// Length of the string literal `s` starts with, quotes included.
+fn string_end(s: &str) -> Option<usize> {
+    let quote = s.chars().next()?;
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '\n' => return None,
+            _ if c == quote => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Read the target of `url(...)` at the start of `s`: quoted or not.
+/// Returns the target, its offset in `s` and the length of the whole
+/// `url(...)`.
+fn parse_url(s: &str) -> Option<(&str, usize, usize)> {
+    let inner = s.strip_prefix("url(")?;
+    let close = inner.find(')')?;
+    let body = &inner[..close];
+    let trimmed = body.trim_start();
+    let start = 4 + body.len() - trimmed.len();
+    let (target, offset) = if trimmed.starts_with('"') || trimmed.starts_with('\'') {
+        let end = string_end(trimmed)?;
+        (&trimmed[1..end - 1], start + 1)
+    } else {
+        (trimmed.trim_end(), start)
+    };
+    Some((target, offset, 4 + close + 1))
+}
+
+/// Read the specifiers of an `@import`, `@use` or `@forward` whose
+/// parameters start at `start`: quoted strings and `url(...)`, separated by
+/// commas. Parenthesized options, like Less's `(reference)`, are skipped.
+/// `@use` and `@forward` load a single module.
+fn at_rule_specifiers(src: &str, start: usize, single: bool, refs: &mut Vec<Reference>) -> usize {
+    let mut i = start;
+    loop {
+        let rest = &src[i..];
+        let trimmed = rest.trim_start_matches([' ', '\t', '\r', '\n', ',']);
+        i += rest.len() - trimmed.len();
+        let (spec, offset, len) = if trimmed.starts_with('"') || trimmed.starts_with('\'') {
+            let Some(end) = string_end(trimmed) else { break };
+            (&trimmed[1..end - 1], i + 1, end)
+        } else if trimmed.starts_with("url(") {
+            let Some((target, offset, len)) = parse_url(trimmed) else { break };
+            (target, i + offset, len)
+        } else if trimmed.starts_with('(') {
+            let Some(close) = trimmed.find(')') else { break };
+            i += close + 1;
+            continue;
+        } else {
+            break;
+        };
+        refs.push(Reference {
+            specifier: spec.to_string(),
+            kind: ImportKind::Style,
+            offset,
+        });
+        i += len;
+        if single {
+            break;
+        }
+    }
+    i
+}
+
+/// Collect the files a stylesheet references: `@import` (with Less options
+/// such as `(reference)`), Sass `@use` and `@forward`, `url(...)` of fonts
+/// and images, and CSS Modules `composes: ... from '...'`.
+pub fn collect_references(src: &str, syntax: Syntax) -> Vec<Reference> {
+    static AT_RULE_RE: OnceLock<Regex> = OnceLock::new();
+    static URL_RE: OnceLock<Regex> = OnceLock::new();
+    static COMPOSES_RE: OnceLock<Regex> = OnceLock::new();
+    let at_rule_re =
+        AT_RULE_RE.get_or_init(|| Regex::new(r"@(import|use|forward)\b").expect("invalid regex"));
+    let url_re = URL_RE.get_or_init(|| Regex::new(r"\burl\(").expect("invalid regex"));
+    let composes_re = COMPOSES_RE.get_or_init(|| {
+        Regex::new(r#"composes\s*:[^;}]*?\sfrom\s+(?:"([^"]*)"|'([^']*)')"#).expect("invalid regex")
+    });
+
+    let src = blank_comments(src, syntax.line_comments());
+    let mut refs = Vec::new();
+    // Parameters of at-rules, whose `url(...)` are already collected
+    let mut at_rules = Vec::new();
+    for m in at_rule_re.find_iter(&src) {
+        let single = m.as_str() != "@import";
+        let end = at_rule_specifiers(&src, m.end(), single, &mut refs);
+        at_rules.push(m.end()..end);
+    }
+    for m in url_re.find_iter(&src) {
+        if at_rules.iter().any(|r| r.contains(&m.start())) {
+            continue;
+        }
+        if let Some((target, offset, _)) = parse_url(&src[m.start()..]) {
+            refs.push(Reference {
+                specifier: target.to_string(),
+                kind: ImportKind::Url,
+                offset: m.start() + offset,
+            });
+        }
+    }
+    for cap in composes_re.captures_iter(&src) {
+        let spec = cap.get(1).or_else(|| cap.get(2)).expect("one quote style matches");
+        refs.push(Reference {
+            specifier: spec.as_str().to_string(),
+            kind: ImportKind::Style,
+            offset: spec.start(),
+        });
+    }
+    refs.sort_by_key(|r| r.offset);
+    refs
+}
+
+/// Whether a specifier names nothing in the project: a remote or inline
+/// resource, a fragment (`url(#gradient)`), a Sass built-in module, or a
+/// path built from variables.
+fn is_ignored(spec: &str) -> bool {
+    // `https:`, `data:`, `sass:`, but not `pkg:` which names a package
+    let has_scheme = spec.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1 && scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-')
+    });
+    spec.is_empty()
+        || spec.starts_with('#')
+        || spec.starts_with("//")
+        || spec.starts_with('$')
+        || spec.starts_with("var(")
+        || spec.contains("#{")
+        || spec.contains("@{")
+        || (has_scheme && !spec.starts_with("pkg:"))
+}
+
+fn is_file(path: &VfsPath) -> bool {
+    path.is_file().unwrap_or(false)
+}
+
+/// Resolve a stylesheet import the way Sass and Less do: the file itself,
+/// then with each extension of `syntax` added, as a Sass partial
+/// (`_name.scss`), and as a folder with an `index` or `_index` file.
+fn resolve_style_path(base: &VfsPath, syntax: Syntax) -> Option<VfsPath> {
+    if is_file(base) {
+        return Some(base.clone());
+    }
+    let has_ext = Path::new(base.as_str())
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| Syntax::from_extension(e).is_some());
+    let parent = base.parent();
+    let name = base.filename();
+    let partials = matches!(syntax, Syntax::Scss | Syntax::Sass);
+    let mut candidates = Vec::new();
+    if has_ext {
+        if partials {
+            candidates.push(parent.join(format!("_{name}")));
+        }
+    } else {
+        for ext in syntax.extensions() {
+            candidates.push(parent.join(format!("{name}.{ext}")));
+            if partials {
+                candidates.push(parent.join(format!("_{name}.{ext}")));
+            }
+        }
+        for ext in syntax.extensions() {
+            candidates.push(base.join(format!("index.{ext}")));
+            if partials {
+                candidates.push(base.join(format!("_index.{ext}")));
+            }
+        }
+    }
+    candidates.into_iter().flatten().find(is_file)
+}
+
+/// Follows the references between stylesheets (`.css`, `.scss`, `.sass`
+/// and `.less`) and from them to fonts and images. Stylesheets are asset
+/// nodes, as when JS imports them. Relative paths that don't resolve become
+/// unresolved nodes; bare `@import`s that don't resolve to a file, and
+/// those written `~pkg/...` or `pkg:...`, are external packages.
+pub struct CssParser;
+
+impl Parser for CssParser {
+    fn name(&self) -> &'static str {
+        "css"
+    }
+
+    fn can_parse(&self, path: &VfsPath) -> bool {
+        Path::new(path.as_str())
+            .extension()
+            .and_then(|s| s.to_str())
+            .and_then(Syntax::from_extension)
+            .is_some()
+    }
+
+    fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
+        let src = path.read_to_string()?;
+        let syntax = Path::new(path.as_str())
+            .extension()
+            .and_then(|s| s.to_str())
+            .and_then(Syntax::from_extension)
+            .unwrap_or(Syntax::Css);
+        let references = collect_references(&src, syntax);
+        Ok(reference_edges(path, &src, references, syntax, ctx))
+    }
+
+    fn file_kind(&self, _path: &VfsPath) -> Option<NodeKind> {
+        Some(NodeKind::Asset)
+    }
+}
+
+/// Resolve references found in `src`, the source of the file at `path`,
+/// into graph edges. `syntax` decides which files an import without an
+/// extension may name, and whether a bare import matching no file names a
+/// package.
+pub fn reference_edges(
+    path: &VfsPath,
+    src: &str,
+    references: Vec<Reference>,
+    syntax: Syntax,
+    ctx: &Context,
+) -> Vec<Edge> {
+    let root_str = ctx.root.as_str().trim_end_matches('/');
+    let rel_of = |p: &VfsPath| {
+        p.as_str()
+            .strip_prefix(root_str)
+            .unwrap_or(p.as_str())
+            .trim_start_matches('/')
+            .to_string()
+    };
+    let rel = rel_of(path);
+    let dir = path.parent();
+    let mut edges = Vec::new();
+    for reference in references {
+        let spec = reference.specifier.as_str();
+        if is_ignored(spec) {
+            continue;
+        }
+        // Fonts are often referenced as `font.woff2?v=4` or `font.svg#name`
+        let path_part = match reference.kind {
+            ImportKind::Url => spec.split(['?', '#']).next().unwrap_or(spec),
+            _ => spec,
+        };
+        let package = path_part
+            .strip_prefix('~')
+            .or_else(|| path_part.strip_prefix("pkg:"));
+        let target = if package.is_some() {
+            None
+        } else if let Some(abs) = path_part.strip_prefix('/') {
+            ctx.root.join(abs).ok().and_then(|p| resolve_style_path(&p, syntax))
+        } else {
+            dir.join(path_part)
+                .ok()
+                .and_then(|p| resolve_style_path(&p, syntax))
+                .or_else(|| resolve_alias_import(ctx.tsconfigs, path, path_part))
+        };
+        // Only Sass and Less look bare imports up in load paths; in plain CSS
+        // an `@import` is a relative URL like `url()`
+        let relative = path_part.starts_with('.') || reference.kind == ImportKind::Url || syntax == Syntax::Css;
+        let (to, to_type) = match (target, package) {
+            (Some(target), _) => {
+                let ext = Path::new(target.as_str())
+                    .extension()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("");
+                let to_type = if is_module_extension(ext) {
+                    None
+                } else {
+                    Some(NodeKind::Asset)
+                };
+                (rel_of(&target), to_type)
+            }
+            (None, Some(package)) => (package.to_string(), Some(NodeKind::External)),
+            // Root-relative URLs may be served from a public folder
+            (None, None) if path_part.starts_with('/') => continue,
+            (None, None) if relative => (
+                unresolved_target(ctx.root, path, &format!("./{}", path_part.trim_start_matches("./"))),
+                Some(NodeKind::Unresolved),
+            ),
+            (None, None) => (path_part.to_string(), Some(NodeKind::External)),
+        };
+        edges.push(Edge {
+            from: rel.clone(),
+            to,
+            kind: EdgeType::Regular,
+            from_type: None,
+            to_type,
+            import_kind: Some(reference.kind),
+            location: Some(Location::from_offset(&rel, src, reference.offset)),
+            specifier: Some(reference.specifier),
+            ..Default::default()
+        });
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::test_util::{TestFS, test_context};
+
+    fn specifiers(src: &str, syntax: Syntax) -> Vec<(String, ImportKind)> {
+        collect_references(src, syntax)
+            .into_iter()
+            .map(|r| (r.specifier, r.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_collect_css_references() {
+        let src = r#"@import "reset.css";
+@import url('theme.css') screen and (min-width: 600px);
+/* @import "commented.css"; */
+@font-face { src: url(fonts/inter.woff2?v=4) format("woff2"), url("https://cdn.example.com/x.woff"); }
+.logo { background: url( "./img/logo.svg" ) no-repeat; }
+.title { composes: heading large from "./typography.css"; }
+"#;
+        assert_eq!(
+            specifiers(src, Syntax::Css),
+            [
+                ("reset.css".to_string(), ImportKind::Style),
+                ("theme.css".to_string(), ImportKind::Style),
+                ("fonts/inter.woff2?v=4".to_string(), ImportKind::Url),
+                ("https://cdn.example.com/x.woff".to_string(), ImportKind::Url),
+                ("./img/logo.svg".to_string(), ImportKind::Url),
+                ("./typography.css".to_string(), ImportKind::Style),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_scss_and_less_references() {
+        let scss = "// @use 'commented';\n@use 'sass:math';\n@use \"config\" with ($primary: \"blue\");\n@forward 'src/list' hide list-reset;\n@import 'a', 'b';\n.x { background: url(//cdn.example.com/bg.png); }";
+        assert_eq!(
+            specifiers(scss, Syntax::Scss),
+            [
+                ("sass:math".to_string(), ImportKind::Style),
+                ("config".to_string(), ImportKind::Style),
+                ("src/list".to_string(), ImportKind::Style),
+                ("a".to_string(), ImportKind::Style),
+                ("b".to_string(), ImportKind::Style),
+                ("//cdn.example.com/bg.png".to_string(), ImportKind::Url),
+            ]
+        );
+        let less = "@import (reference) \"mixins\";\n@import (css, optional) 'extra.css';";
+        assert_eq!(
+            specifiers(less, Syntax::Less),
+            [("mixins".to_string(), ImportKind::Style), ("extra.css".to_string(), ImportKind::Style)]
+        );
+    }
+
+    #[test]
+    fn test_css_edges() {
+        let fs = TestFS::new([
+            (
+                "styles/main.scss",
+                "@use 'sass:math';\n@use 'variables';\n@use 'components';\n@import '~bootstrap/scss/grid';\n@import 'missing/thing';\n.hero { background: url('../img/hero.png'); }\n.icon { background: url(../img/gone.png); }\n.t { background: url(#{$path}/x.png); }",
+            ),
+            ("styles/site.css", "@import 'typo.css';\n@import '~normalize.css';\n@import 'reset.css';"),
+            ("styles/reset.css", ""),
+            ("styles/_variables.scss", ""),
+            ("styles/components/_index.scss", ""),
+            ("img/hero.png", ""),
+        ]);
+        let root = fs.root();
+        let ctx = test_context(&root);
+        let edges = CssParser.parse(&root.join("styles/main.scss").unwrap(), &ctx).unwrap();
+        let edges: Vec<_> = edges.iter().map(|e| (e.to.as_str(), e.to_type)).collect();
+        assert_eq!(
+            edges,
+            [
+                ("styles/_variables.scss", Some(NodeKind::Asset)),
+                ("styles/components/_index.scss", Some(NodeKind::Asset)),
+                ("bootstrap/scss/grid", Some(NodeKind::External)),
+                ("missing/thing", Some(NodeKind::External)),
+                ("img/hero.png", Some(NodeKind::Asset)),
+                ("unresolved:img/gone.png", Some(NodeKind::Unresolved)),
+            ]
+        );
+
+        let edges = CssParser.parse(&root.join("styles/site.css").unwrap(), &ctx).unwrap();
+        let edges: Vec<_> = edges.iter().map(|e| (e.to.as_str(), e.to_type)).collect();
+        assert_eq!(
+            edges,
+            [
+                ("unresolved:styles/typo.css", Some(NodeKind::Unresolved)),
+                ("normalize.css", Some(NodeKind::External)),
+                ("styles/reset.css", Some(NodeKind::Asset)),
+            ]
+        );
+    }
+}
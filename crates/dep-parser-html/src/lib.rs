@@ -1,13 +1,251 @@
-use regex::Regex;
+use std::ops::Range;
 use std::path::Path;
-use std::sync::OnceLock;
+use swc_common::FileName;
 use vfs::VfsPath;
 
 use dep_core::js_resolve::{
-    JS_EXTENSIONS, is_node_builtin, resolve_alias_import, resolve_relative_import,
+    is_module_extension, is_remote, resolve_alias_import, resolve_relative_import, resolve_ts_relative_import,
+    unresolved_target,
 };
-use dep_core::{Context, Edge, Parser};
-use dep_core::{NodeKind, EdgeType};
+use dep_core::import_map::ImportMap;
+use dep_core::{Context, Edge, Location, LogLevel, Parser};
+use dep_core::{ImportKind, NodeKind, EdgeType};
+use dep_parser_js::{ModuleInfo, blank_except, module_edges, parse_source};
+
+pub mod tokenizer;
+
+use tokenizer::{StartTag, Token, decode, tokenize};
+
+/// A file an HTML page loads through an attribute, such as `<script src>` or
+/// `<img srcset>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reference {
+    /// The URL as written, with character references decoded
+    pub url: String,
+    pub kind: ImportKind,
+    /// Byte offset of the URL in the page
+    pub offset: usize,
+}
+
+/// What an HTML page loads.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Page {
+    pub references: Vec<Reference>,
+    /// Byte ranges of the bodies of inline `<script type="module">` elements
+    pub module_scripts: Vec<Range<usize>>,
+    /// Byte ranges of the bodies of `<script type="importmap">` elements
+    pub import_maps: Vec<Range<usize>>,
+}
+
+enum ScriptType {
+    Classic,
+    Module,
+    ImportMap,
+    /// JSON, templates and other data blocks the browser does not run
+    Data,
+}
+
+fn script_type(tag: &StartTag) -> ScriptType {
+    let Some(attr) = tag.attr("type") else {
+        return ScriptType::Classic;
+    };
+    let value = attr.value().trim().to_ascii_lowercase();
+    match value.as_str() {
+        "module" => ScriptType::Module,
+        "importmap" => ScriptType::ImportMap,
+        "" | "text/javascript" | "application/javascript" | "text/ecmascript" | "application/ecmascript"
+        | "application/x-javascript" => ScriptType::Classic,
+        _ => ScriptType::Data,
+    }
+}
+
+/// The URLs of a `srcset` attribute with their offsets in it, skipping the
+/// width and density descriptors.
+fn srcset_urls(srcset: &str) -> Vec<(&str, usize)> {
+    let mut urls = Vec::new();
+    let mut rest = srcset;
+    loop {
+        let trimmed = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        if trimmed.is_empty() {
+            return urls;
+        }
+        let offset = srcset.len() - trimmed.len();
+        let end = trimmed.find(|c: char| c.is_ascii_whitespace()).unwrap_or(trimmed.len());
+        let url = &trimmed[..end];
+        // A URL directly followed by a comma has no descriptors
+        let candidate_end = if url.ends_with(',') {
+            end
+        } else {
+            trimmed[end..].find(',').map_or(trimmed.len(), |i| end + i + 1)
+        };
+        urls.push((url.trim_end_matches(','), offset));
+        rest = &trimmed[candidate_end..];
+    }
+}
+
+/// Collect what an HTML page loads: scripts, stylesheets, module preloads,
+/// icons, the web app manifest, images, media sources and video posters, and
+/// the bodies of inline module scripts and import maps.
+pub fn collect_references(src: &str) -> Page {
+    let mut page = Page::default();
+    let mut tokens = tokenize(src).into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let Token::StartTag(tag) = token else { continue };
+        let mut push = |attr: &str, kind: ImportKind| {
+            if let Some(attr) = tag.attr(attr) {
+                page.references.push(Reference {
+                    url: attr.value().trim().to_string(),
+                    kind,
+                    offset: attr.offset,
+                });
+            }
+        };
+        match tag.name.as_str() {
+            "script" => {
+                match (script_type(&tag), tokens.peek()) {
+                    (ScriptType::Data, _) => {}
+                    (ScriptType::ImportMap, Some(Token::Text(body))) => page.import_maps.push(body.clone()),
+                    (ScriptType::ImportMap, _) => {}
+                    _ if tag.attr("src").is_some() => push("src", ImportKind::Script),
+                    (ScriptType::Module, Some(Token::Text(body))) => page.module_scripts.push(body.clone()),
+                    _ => {}
+                }
+            }
+            "link" => {
+                let rel = tag.attr("rel").map(|a| a.value().to_ascii_lowercase()).unwrap_or_default();
+                let kind = rel.split_ascii_whitespace().find_map(|rel| match rel {
+                    "stylesheet" => Some(ImportKind::Style),
+                    "modulepreload" => Some(ImportKind::Script),
+                    "icon" | "apple-touch-icon" | "mask-icon" | "manifest" => Some(ImportKind::Url),
+                    _ => None,
+                });
+                if let Some(kind) = kind {
+                    push("href", kind);
+                }
+            }
+            "img" | "source" => {
+                push("src", ImportKind::Url);
+                if let Some(srcset) = tag.attr("srcset") {
+                    for (url, offset) in srcset_urls(srcset.raw) {
+                        page.references.push(Reference {
+                            url: decode(url).into_owned(),
+                            kind: ImportKind::Url,
+                            offset: srcset.offset + offset,
+                        });
+                    }
+                }
+            }
+            "video" => {
+                push("src", ImportKind::Url);
+                push("poster", ImportKind::Url);
+            }
+            "audio" | "track" => push("src", ImportKind::Url),
+            _ => {}
+        }
+    }
+    page
+}
+
+/// Whether a URL names nothing in the project: a remote or inline resource,
+/// a fragment, or a placeholder filled in by a template engine or bundler.
+fn is_ignored(url: &str) -> bool {
+    // `https:`, `data:`, `mailto:`, `javascript:`...
+    let has_scheme = url.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1 && scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+    });
+    url.is_empty()
+        || url.starts_with('#')
+        || url.starts_with("//")
+        || url.starts_with('%')
+        || url.contains("{{")
+        || url.contains("<%")
+        || has_scheme
+}
+
+/// Edges for the references of a page. URLs are relative to the page, or to
+/// the project root when they start with `/`; a root-relative URL matching
+/// no file is skipped since it may be served from a public folder. Remote
+/// scripts and stylesheets, such as those of a CDN, are external nodes
+/// named by their URL.
+pub fn reference_edges(path: &VfsPath, src: &str, references: Vec<Reference>, ctx: &Context) -> Vec<Edge> {
+    let root_str = ctx.root.as_str().trim_end_matches('/');
+    let rel_of = |p: &VfsPath| {
+        p.as_str()
+            .strip_prefix(root_str)
+            .unwrap_or(p.as_str())
+            .trim_start_matches('/')
+            .to_string()
+    };
+    let rel = rel_of(path);
+    let mut edges = Vec::new();
+    for reference in references {
+        let url = reference.url.as_str();
+        let remote = is_remote(url) && matches!(reference.kind, ImportKind::Script | ImportKind::Style);
+        if is_ignored(url) && !remote {
+            continue;
+        }
+        let path_part = url.split(['?', '#']).next().unwrap_or(url);
+        let relative = if path_part.starts_with('.') {
+            path_part.to_string()
+        } else {
+            format!("./{path_part}")
+        };
+        let target = if remote {
+            None
+        } else if let Some(abs) = path_part.strip_prefix('/') {
+            resolve_relative_import(ctx.root, abs)
+        } else {
+            resolve_ts_relative_import(ctx.tsconfigs, path, &relative)
+                .or_else(|| resolve_alias_import(ctx.tsconfigs, path, path_part))
+        };
+        let (to, to_type) = match target {
+            Some(target) => {
+                let ext = Path::new(target.as_str())
+                    .extension()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("");
+                let to_type = if is_module_extension(ext) {
+                    None
+                } else {
+                    Some(NodeKind::Asset)
+                };
+                (rel_of(&target), to_type)
+            }
+            None if remote => (url.to_string(), Some(NodeKind::External)),
+            None if path_part.starts_with('/') => continue,
+            None => (unresolved_target(ctx.root, path, &relative), Some(NodeKind::Unresolved)),
+        };
+        edges.push(Edge {
+            from: rel.clone(),
+            to,
+            kind: EdgeType::Regular,
+            from_type: None,
+            to_type,
+            import_kind: Some(reference.kind),
+            location: Some(Location::from_offset(&rel, src, reference.offset)),
+            specifier: Some(reference.url),
+            ..Default::default()
+        });
+    }
+    edges
+}
+
+/// The import maps a page declares in `<script type="importmap">`.
+pub fn page_import_maps(path: &VfsPath, src: &str, page: &Page) -> anyhow::Result<Vec<ImportMap>> {
+    page.import_maps
+        .iter()
+        .map(|body| {
+            let json: serde_json::Value = serde_json::from_str(&src[body.clone()])?;
+            Ok(ImportMap::from_json(path, &json))
+        })
+        .collect()
+}
+
+/// Read the import maps an HTML page declares.
+pub fn read_import_maps(path: &VfsPath) -> anyhow::Result<Vec<ImportMap>> {
+    let src = path.read_to_string()?;
+    page_import_maps(path, &src, &collect_references(&src))
+}
 
 pub struct HtmlParser;
 
@@ -24,68 +262,152 @@ impl Parser for HtmlParser {
 
     fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
         let src = path.read_to_string()?;
-        let root_str = ctx.root.as_str().trim_end_matches('/');
-        let rel = path
-            .as_str()
-            .strip_prefix(root_str)
-            .unwrap_or(path.as_str())
-            .trim_start_matches('/');
-        let mut edges = Vec::new();
-        static SCRIPT_RE: OnceLock<Regex> = OnceLock::new();
-        let re = SCRIPT_RE.get_or_init(|| Regex::new(r#"<script[^>]*src=[\"']([^\"']+)[\"'][^>]*>"#).expect("invalid regex"));
-        for cap in re.captures_iter(&src) {
-            let spec = cap[1].to_string();
-            let (target_str, to_type) = if spec.starts_with('.') {
-                if let Some(target) = resolve_relative_import(&path.parent(), &spec) {
-                    let target_rel = target
-                        .as_str()
-                        .strip_prefix(root_str)
-                        .unwrap_or(target.as_str())
-                        .trim_start_matches('/')
-                        .to_string();
-                    let ext = Path::new(target.as_str())
-                        .extension()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("");
-                    let to_type = if JS_EXTENSIONS.contains(&ext) {
-                        None
-                    } else {
-                        Some(NodeKind::Asset)
-                    };
-                    (target_rel, to_type)
-                } else {
-                    continue;
-                }
-            } else if let Some(target) = resolve_alias_import(ctx.aliases, &spec) {
-                let target_rel = target
-                    .as_str()
-                    .strip_prefix(root_str)
-                    .unwrap_or(target.as_str())
-                    .trim_start_matches('/')
-                    .to_string();
-                let ext = Path::new(target.as_str())
-                    .extension()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("");
-                let to_type = if JS_EXTENSIONS.contains(&ext) {
-                    None
-                } else {
-                    Some(NodeKind::Asset)
-                };
-                (target_rel, to_type)
-            } else if is_node_builtin(&spec) {
-                (spec.clone(), Some(NodeKind::Builtin))
-            } else {
-                (spec.clone(), Some(NodeKind::External))
-            };
-            edges.push(Edge {
-                from: rel.to_string(),
-                to: target_str,
-                kind: EdgeType::Regular,
-                from_type: None,
-                to_type,
-            });
+        let page = collect_references(&src);
+        // An invalid map was already reported when import maps were loaded
+        let import_maps = page_import_maps(path, &src, &page).unwrap_or_default();
+        // The page's own import map is the one its scripts are resolved with
+        let ctx = &Context {
+            import_maps: if import_maps.is_empty() { ctx.import_maps } else { &import_maps },
+            ..*ctx
+        };
+        let mut info = ModuleInfo::default();
+        for body in page.module_scripts {
+            let code = blank_except(&src, body);
+            match parse_source(&code, "js", FileName::Custom(path.as_str().into())) {
+                // Nothing can import an inline script, so its exports are dropped
+                Ok(script) => info.imports.extend(script.imports),
+                Err(err) => ctx.logger.log(
+                    LogLevel::Error,
+                    &format!("Failed to parse inline module script in {}: {err}", path.as_str()),
+                ),
+            }
         }
+        let mut edges = module_edges(path, &src, info, ctx);
+        edges.extend(reference_edges(path, &src, page.references, ctx));
         Ok(edges)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::test_util::{TestFS, test_context};
+
+    #[test]
+    fn test_srcset_urls() {
+        let srcset = "a.png 1x, b-2x.png 2x,c.png,\n  d.png 640w";
+        let urls: Vec<_> = srcset_urls(srcset).into_iter().map(|(url, _)| url).collect();
+        assert_eq!(urls, ["a.png", "b-2x.png", "c.png", "d.png"]);
+        let (url, offset) = srcset_urls(srcset)[1];
+        assert_eq!(&srcset[offset..offset + url.len()], "b-2x.png");
+    }
+
+    #[test]
+    fn test_collect_references() {
+        let src = r#"<!doctype html>
+<html>
+<head>
+  <link rel="stylesheet" href="./main.css">
+  <link rel=modulepreload href=/src/chunk.js>
+  <link rel="shortcut icon" href="favicon.ico">
+  <link rel="manifest" href="/site.webmanifest">
+  <link rel="preconnect" href="https://fonts.example.com">
+  <script data-x="a>b" src="./app.js" defer></script>
+  <script type="application/ld+json">{"url": "./not-a-file"}</script>
+  <script type="module">import { start } from './main.ts'; start();</script>
+</head>
+<body>
+  <!-- <img src="./commented.png"> -->
+  <img alt="logo" src="img/logo.png" srcset="img/logo@2x.png 2x, img/logo@3x.png 3x">
+  <picture><source type="image/webp" srcset="img/hero.webp"></picture>
+  <video poster="img/poster.jpg"><source src="media/intro.mp4"></video>
+</body>
+</html>"#;
+        let page = collect_references(src);
+        let refs: Vec<_> = page.references.iter().map(|r| (r.url.as_str(), r.kind)).collect();
+        assert_eq!(
+            refs,
+            [
+                ("./main.css", ImportKind::Style),
+                ("/src/chunk.js", ImportKind::Script),
+                ("favicon.ico", ImportKind::Url),
+                ("/site.webmanifest", ImportKind::Url),
+                ("./app.js", ImportKind::Script),
+                ("img/logo.png", ImportKind::Url),
+                ("img/logo@2x.png", ImportKind::Url),
+                ("img/logo@3x.png", ImportKind::Url),
+                ("img/hero.webp", ImportKind::Url),
+                ("img/poster.jpg", ImportKind::Url),
+                ("media/intro.mp4", ImportKind::Url),
+            ]
+        );
+        for r in &page.references {
+            assert!(src[r.offset..].starts_with(&r.url));
+        }
+        assert_eq!(page.module_scripts.len(), 1);
+        assert_eq!(&src[page.module_scripts[0].clone()], "import { start } from './main.ts'; start();");
+    }
+
+    #[test]
+    fn test_html_edges() {
+        let fs = TestFS::new([
+            (
+                "site/index.html",
+                "<link rel=stylesheet href=style.css?v=2>\n<script type=module>\nimport './boot';\nimport 'lit';\n</script>\n<img src=\"/logo.svg\"><img src=\"/public-only.png\"><img src=\"missing.png\">\n<script src=\"https://cdn.example.com/analytics.js\"></script><link rel=stylesheet href=//fonts.example.com/inter.css><img src=\"https://cdn.example.com/banner.png\">",
+            ),
+            ("site/style.css", ""),
+            ("site/boot.ts", ""),
+            ("logo.svg", ""),
+        ]);
+        let root = fs.root();
+        let ctx = test_context(&root);
+        let edges = HtmlParser.parse(&root.join("site/index.html").unwrap(), &ctx).unwrap();
+        let edges: Vec<_> = edges
+            .iter()
+            .map(|e| (e.to.as_str(), e.to_type, e.location.as_ref().map(|l| l.line)))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("site/boot.ts", None, Some(3)),
+                ("lit", Some(NodeKind::External), Some(4)),
+                ("site/style.css", Some(NodeKind::Asset), Some(1)),
+                ("logo.svg", Some(NodeKind::Asset), Some(6)),
+                ("unresolved:site/missing.png", Some(NodeKind::Unresolved), Some(6)),
+                ("https://cdn.example.com/analytics.js", Some(NodeKind::External), Some(7)),
+                ("//fonts.example.com/inter.css", Some(NodeKind::External), Some(7)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_page_import_map() {
+        let fs = TestFS::new([
+            (
+                "index.html",
+                r#"<script type="importmap">
+{ "imports": { "lit": "/vendor/lit.js", "app/": "./src/" } }
+</script>
+<script type="module">
+import { html } from 'lit';
+import 'app/main.js';
+import 'react';
+</script>"#,
+            ),
+            ("vendor/lit.js", ""),
+            ("src/main.js", ""),
+        ]);
+        let root = fs.root();
+        let ctx = test_context(&root);
+        let edges = HtmlParser.parse(&root.join("index.html").unwrap(), &ctx).unwrap();
+        let edges: Vec<_> = edges.iter().map(|e| (e.to.as_str(), e.to_type)).collect();
+        assert_eq!(
+            edges,
+            [
+                ("vendor/lit.js", None),
+                ("src/main.js", None),
+                ("react", Some(NodeKind::External)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// Elements whose content is text up to the matching end tag, not markup.
+const RAW_TEXT_ELEMENTS: &[&str] = &[
+    "script", "style", "textarea", "title", "xmp", "iframe", "noembed", "noframes",
+];
+
+/// An attribute of a start tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attribute<'a> {
+    /// Name, lowercased
+    pub name: String,
+    /// Value as written, before character references are decoded. Empty for
+    /// an attribute without a value
+    pub raw: &'a str,
+    /// Byte offset of the value in the document
+    pub offset: usize,
+}
+
+impl<'a> Attribute<'a> {
+    /// The value with character references such as `&amp;` decoded.
+    pub fn value(&self) -> Cow<'a, str> {
+        decode(self.raw)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StartTag<'a> {
+    /// Tag name, lowercased
+    pub name: String,
+    pub attrs: Vec<Attribute<'a>>,
+    pub self_closing: bool,
+}
+
+impl<'a> StartTag<'a> {
+    /// The first attribute called `name`; later duplicates are ignored like
+    /// browsers do.
+    pub fn attr(&self, name: &str) -> Option<&Attribute<'a>> {
+        self.attrs.iter().find(|a| a.name == name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token<'a> {
+    StartTag(StartTag<'a>),
+    /// End tag with its lowercased name
+    EndTag(String),
+    /// Byte range of text, or of the content of a raw text element such as
+    /// `<script>` or `<style>`
+    Text(Range<usize>),
+}
+
+/// Split an HTML document into tags and text. Comments, doctypes and
+/// processing instructions are skipped, and the content of raw text elements
+/// is a single text token. Like a browser, this never fails: a `<` that does
+/// not start a tag is text, and a tag cut off by the end of the document is
+/// dropped.
+pub fn tokenize(src: &str) -> Vec<Token<'_>> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut pos = 0;
+    while let Some(lt) = find_byte(bytes, pos, b'<') {
+        let (token, end) = match bytes.get(lt + 1) {
+            Some(b'!') if src[lt..].starts_with("<!--") => {
+                let end = src[lt + 4..].find("-->").map_or(src.len(), |i| lt + 4 + i + 3);
+                (None, end)
+            }
+            Some(b'!' | b'?') => (None, find_byte(bytes, lt, b'>').map_or(src.len(), |i| i + 1)),
+            Some(b'/') if bytes.get(lt + 2).is_some_and(u8::is_ascii_alphabetic) => {
+                let name_end = tag_name_end(bytes, lt + 2);
+                let end = find_byte(bytes, name_end, b'>').map_or(src.len(), |i| i + 1);
+                (Some(Token::EndTag(src[lt + 2..name_end].to_ascii_lowercase())), end)
+            }
+            Some(c) if c.is_ascii_alphabetic() => match start_tag(src, lt) {
+                Some((tag, end)) => (Some(Token::StartTag(tag)), end),
+                None => (None, src.len()),
+            },
+            _ => {
+                pos = lt + 1;
+                continue;
+            }
+        };
+        if text_start < lt {
+            tokens.push(Token::Text(text_start..lt));
+        }
+        pos = end;
+        text_start = end;
+        let Some(token) = token else { continue };
+        let raw_text = match &token {
+            Token::StartTag(tag) if !tag.self_closing && RAW_TEXT_ELEMENTS.contains(&tag.name.as_str()) => {
+                Some(tag.name.clone())
+            }
+            _ => None,
+        };
+        tokens.push(token);
+        if let Some(name) = raw_text {
+            let close = find_end_tag(src, end, &name);
+            if end < close {
+                tokens.push(Token::Text(end..close));
+            }
+            if close < src.len() {
+                tokens.push(Token::EndTag(name));
+            }
+            pos = find_byte(bytes, close, b'>').map_or(src.len(), |i| i + 1);
+            text_start = pos;
+        }
+    }
+    if text_start < src.len() {
+        tokens.push(Token::Text(text_start..src.len()));
+    }
+    tokens
+}
+
+fn find_byte(bytes: &[u8], from: usize, byte: u8) -> Option<usize> {
+    bytes.get(from..)?.iter().position(|b| *b == byte).map(|i| from + i)
+}
+
+fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b'\x0c')
+}
+
+fn tag_name_end(bytes: &[u8], from: usize) -> usize {
+    (from..bytes.len())
+        .find(|&i| is_space(bytes[i]) || bytes[i] == b'/' || bytes[i] == b'>')
+        .unwrap_or(bytes.len())
+}
+
+/// Offset of the `</name` closing a raw text element, or the end of the
+/// document if it is never closed.
+fn find_end_tag(src: &str, from: usize, name: &str) -> usize {
+    let bytes = src.as_bytes();
+    let mut pos = from;
+    while let Some(lt) = find_byte(bytes, pos, b'<') {
+        let name_start = lt + 2;
+        let name_end = name_start + name.len();
+        if bytes.get(lt + 1) == Some(&b'/')
+            && src
+                .get(name_start..name_end)
+                .is_some_and(|n| n.eq_ignore_ascii_case(name))
+            && bytes
+                .get(name_end)
+                .is_none_or(|b| is_space(*b) || *b == b'/' || *b == b'>')
+        {
+            return lt;
+        }
+        pos = lt + 1;
+    }
+    src.len()
+}
+
+/// Read the start tag at `lt`, returning it with the offset just past its
+/// `>`, or `None` if the document ends first.
+fn start_tag(src: &str, lt: usize) -> Option<(StartTag<'_>, usize)> {
+    let bytes = src.as_bytes();
+    let mut i = tag_name_end(bytes, lt + 1);
+    let mut tag = StartTag {
+        name: src[lt + 1..i].to_ascii_lowercase(),
+        attrs: Vec::new(),
+        self_closing: false,
+    };
+    loop {
+        while i < bytes.len() && (is_space(bytes[i]) || bytes[i] == b'/') {
+            tag.self_closing = bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'>');
+            i += 1;
+        }
+        match bytes.get(i) {
+            None => return None,
+            Some(b'>') => return Some((tag, i + 1)),
+            Some(_) => {}
+        }
+        tag.self_closing = false;
+        // The first character of a name may be `=`
+        let name_start = i;
+        i += 1;
+        while i < bytes.len() && !is_space(bytes[i]) && !matches!(bytes[i], b'/' | b'>' | b'=') {
+            i += 1;
+        }
+        let name = src[name_start..i].to_ascii_lowercase();
+        let mut j = i;
+        while j < bytes.len() && is_space(bytes[j]) {
+            j += 1;
+        }
+        if bytes.get(j) != Some(&b'=') {
+            tag.attrs.push(Attribute { name, raw: "", offset: name_start });
+            continue;
+        }
+        j += 1;
+        while j < bytes.len() && is_space(bytes[j]) {
+            j += 1;
+        }
+        let (raw, offset) = match bytes.get(j) {
+            Some(quote @ (b'"' | b'\'')) => {
+                let close = find_byte(bytes, j + 1, *quote)?;
+                i = close + 1;
+                (&src[j + 1..close], j + 1)
+            }
+            _ => {
+                i = j;
+                while i < bytes.len() && !is_space(bytes[i]) && bytes[i] != b'>' {
+                    i += 1;
+                }
+                (&src[j..i], j)
+            }
+        };
+        tag.attrs.push(Attribute { name, raw, offset });
+    }
+}
+
+/// Decode the character references most likely to appear in a URL.
+pub fn decode(raw: &str) -> Cow<'_, str> {
+    if !raw.contains('&') {
+        return Cow::Borrowed(raw);
+    }
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let reference = rest[1..].find(';').filter(|end| *end <= 8).and_then(|end| {
+            let c = match &rest[1..1 + end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                num => match num.strip_prefix('#')? {
+                    hex if hex.starts_with(['x', 'X']) => char::from_u32(u32::from_str_radix(&hex[1..], 16).ok()?)?,
+                    dec => char::from_u32(dec.parse().ok()?)?,
+                },
+            };
+            Some((c, end + 2))
+        });
+        match reference {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(src: &str) -> Vec<(String, Vec<(String, String)>)> {
+        tokenize(src)
+            .into_iter()
+            .filter_map(|t| match t {
+                Token::StartTag(tag) => Some((
+                    tag.name,
+                    tag.attrs.iter().map(|a| (a.name.clone(), a.value().into_owned())).collect(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_attributes() {
+        let src = r#"<IMG alt="a > b" data-x='1' SRC=logo.png?v=1&amp;w=2 hidden><br/>"#;
+        let attrs = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect()
+        };
+        assert_eq!(
+            tags(src),
+            [
+                (
+                    "img".to_string(),
+                    attrs(&[("alt", "a > b"), ("data-x", "1"), ("src", "logo.png?v=1&w=2"), ("hidden", "")])
+                ),
+                ("br".to_string(), Vec::new()),
+            ]
+        );
+        let Token::StartTag(img) = &tokenize(src)[0] else { panic!("expected a start tag") };
+        let src_attr = img.attr("src").unwrap();
+        assert_eq!(&src[src_attr.offset..src_attr.offset + src_attr.raw.len()], "logo.png?v=1&amp;w=2");
+    }
+
+    #[test]
+    fn test_raw_text_and_comments() {
+        let src = "<!DOCTYPE html><!-- <img src=a.png> --><script>if (a < b) document.write('<img src=b.png>')</SCRIPT ><p>x</p>";
+        let tokens = tokenize(src);
+        assert_eq!(tags(src).iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>(), ["script", "p"]);
+        let Token::Text(body) = tokens[1].clone() else { panic!("expected the script body") };
+        assert_eq!(&src[body], "if (a < b) document.write('<img src=b.png>')");
+        assert_eq!(tokens[2], Token::EndTag("script".into()));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert_eq!(tags("a < b <p"), []);
+        assert_eq!(tags("<script src='x.js'>"), [("script".to_string(), vec![("src".to_string(), "x.js".to_string())])]);
+        assert_eq!(tags("<a href='unterminated>"), []);
+    }
+}
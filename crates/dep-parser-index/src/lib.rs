@@ -43,6 +43,7 @@ impl Parser for IndexParser {
             kind: EdgeType::SameAs,
             from_type: Some(NodeKind::Folder),
             to_type: None,
+            ..Default::default()
         }])
     }
 }
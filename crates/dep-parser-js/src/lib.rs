@@ -1,20 +1,58 @@
 use regex::Regex;
+use std::ops::Range;
 use std::path::Path;
 use std::sync::OnceLock;
 use vfs::VfsPath;
 
 use dep_core::js_resolve::{
-    JS_EXTENSIONS, is_node_builtin, resolve_alias_import, resolve_relative_import,
+    JS_EXTENSIONS, PackageTarget, is_module_extension, is_node_builtin, resolve_alias_import, resolve_import_map,
+    resolve_package_import, resolve_ts_relative_import, unresolved_target,
+};
+use dep_core::{Binding, Context, Edge, Location, Parser, Logger, symbol_node_name};
+use dep_core::{ImportKind, NodeKind, EdgeType};
+use swc_common::{FileName, SourceFile, SourceMap, Span, Spanned, SyntaxContext, sync::Lrc};
+use swc_ecma_ast::{
+    CallExpr, Callee, Decl, ExportAll, ExportSpecifier, Expr, ExprOrSpread, ImportDecl,
+    ImportSpecifier, Lit, MemberExpr, MemberProp, Module, ModuleDecl, ModuleExportName,
+    ModuleItem, NamedExport, ObjectPatProp, Pat, Str, TsEntityName, TsImportEqualsDecl,
+    TsImportType, TsModuleName, TsModuleRef,
 };
-use dep_core::{Context, Edge, Parser, Logger};
-use dep_core::{NodeKind, EdgeType};
-use swc_common::{FileName, SourceMap, sync::Lrc};
-use swc_ecma_ast::{Module, ModuleDecl, ModuleItem};
 use swc_ecma_parser::{EsConfig, Parser as SwcParser, StringInput, Syntax, TsConfig};
+use swc_ecma_visit::{Visit, VisitWith};
+
+/// An import specifier found in a module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Import {
+    pub specifier: String,
+    pub kind: ImportKind,
+    /// Span of the specifier string literal
+    pub span: Span,
+    /// Bindings the import consumes from the target module
+    pub bindings: Vec<Binding>,
+}
+
+/// A name exported by a module itself, not re-exported from another module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Export {
+    pub name: String,
+    /// Span of the exported name
+    pub span: Span,
+}
+
+/// Imports and exports found in a module.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModuleInfo {
+    pub imports: Vec<Import>,
+    pub exports: Vec<Export>,
+}
 
 pub fn parse_module(src: &str, ext: &str, file: FileName) -> anyhow::Result<Module> {
     let cm: Lrc<SourceMap> = Default::default();
     let fm = cm.new_source_file(file, src.into());
+    parse_source_file(&fm, ext)
+}
+
+fn parse_source_file(fm: &SourceFile, ext: &str) -> anyhow::Result<Module> {
     let syntax = match ext {
         "ts" | "tsx" | "mts" | "cts" => Syntax::Typescript(TsConfig {
             tsx: true,
@@ -25,53 +63,406 @@ pub fn parse_module(src: &str, ext: &str, file: FileName) -> anyhow::Result<Modu
             ..Default::default()
         }),
     };
-    let mut parser = SwcParser::new(syntax, StringInput::from(&*fm), None);
+    let mut parser = SwcParser::new(syntax, StringInput::from(fm), None);
     parser
         .parse_module()
         .map_err(|e| anyhow::anyhow!(format!("{:?}", e)))
 }
 
-/// Parse a JS/TS file and return the list of relative imports.
-pub fn parse_file(path: &VfsPath, _logger: &dyn Logger) -> anyhow::Result<Vec<String>> {
+/// Parse JS/TS source and return its imports and exports. Spans are byte
+/// offsets from the start of `src`.
+pub fn parse_source(src: &str, ext: &str, file: FileName) -> anyhow::Result<ModuleInfo> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(file, src.into());
+    let module = parse_source_file(&fm, ext)?;
+    let rebase = |span: Span| {
+        Span::new(
+            span.lo - fm.start_pos,
+            span.hi - fm.start_pos,
+            SyntaxContext::empty(),
+        )
+    };
+    Ok(ModuleInfo {
+        imports: collect_imports(&module)
+            .into_iter()
+            .map(|i| Import {
+                span: rebase(i.span),
+                ..i
+            })
+            .collect(),
+        exports: collect_exports(&module)
+            .into_iter()
+            .map(|e| Export {
+                span: rebase(e.span),
+                ..e
+            })
+            .collect(),
+    })
+}
+
+/// Blank out everything in `src` except `keep`, to parse a script embedded
+/// in a component or page. Byte offsets and line breaks are preserved so
+/// spans still point into the original source.
+pub fn blank_except(src: &str, keep: Range<usize>) -> String {
+    let mut out = String::with_capacity(src.len());
+    for (i, c) in src.char_indices() {
+        if keep.contains(&i) || c == '\n' {
+            out.push(c);
+        } else {
+            out.extend(std::iter::repeat_n(' ', c.len_utf8()));
+        }
+    }
+    out
+}
+
+/// Elements of a single-file component found by [`blocks`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockTag {
+    Script,
+    Style,
+}
+
+/// A `<script>` or `<style>` element of a single-file component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block {
+    /// Attributes with their values and the byte offset of each value
+    pub attrs: Vec<(String, Option<(String, usize)>)>,
+    /// Byte range of the element's content in the component source
+    pub content: Range<usize>,
+}
+
+impl Block {
+    /// The value of an attribute and its byte offset, if it has a value.
+    pub fn attr(&self, name: &str) -> Option<&(String, usize)> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, v)| v.as_ref())
+    }
+
+    /// Whether the element has an attribute, with or without a value.
+    pub fn has_attr(&self, name: &str) -> bool {
+        self.attrs.iter().any(|(n, _)| n == name)
+    }
+}
+
+fn parse_attrs(src: &str, offset: usize) -> Vec<(String, Option<(String, usize)>)> {
+    static ATTR_RE: OnceLock<Regex> = OnceLock::new();
+    let re = ATTR_RE.get_or_init(|| {
+        Regex::new(r#"([^\s"'<>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?"#)
+            .expect("invalid regex")
+    });
+    re.captures_iter(src)
+        .map(|cap| {
+            let value = cap
+                .get(2)
+                .or_else(|| cap.get(3))
+                .or_else(|| cap.get(4))
+                .map(|v| (v.as_str().to_string(), offset + v.start()));
+            (cap[1].to_string(), value)
+        })
+        .collect()
+}
+
+/// Find the `<script>` or `<style>` elements of a component, from byte
+/// `from` of `src` on.
+pub fn blocks(src: &str, tag: BlockTag, from: usize) -> Vec<Block> {
+    static SCRIPT_RE: OnceLock<Regex> = OnceLock::new();
+    static STYLE_RE: OnceLock<Regex> = OnceLock::new();
+    let re = match tag {
+        BlockTag::Script => SCRIPT_RE
+            .get_or_init(|| Regex::new(r"(?s)<script\b([^>]*)>(.*?)</script\s*>").expect("invalid regex")),
+        BlockTag::Style => STYLE_RE
+            .get_or_init(|| Regex::new(r"(?s)<style\b([^>]*)>(.*?)</style\s*>").expect("invalid regex")),
+    };
+    re.captures_iter(&src[from..])
+        .map(|cap| {
+            let attrs = cap.get(1).expect("group 1 always matches");
+            let content = cap.get(2).expect("group 2 always matches").range();
+            Block {
+                attrs: parse_attrs(attrs.as_str(), from + attrs.start()),
+                content: from + content.start..from + content.end,
+            }
+        })
+        .collect()
+}
+
+/// Parse JS/TS source and return its imports. Spans are byte offsets from the
+/// start of `src`.
+pub fn parse_imports(src: &str, ext: &str, file: FileName) -> anyhow::Result<Vec<Import>> {
+    Ok(parse_source(src, ext, file)?.imports)
+}
+
+/// Parse a JS/TS file and return its imports.
+pub fn parse_file(path: &VfsPath, _logger: &dyn Logger) -> anyhow::Result<Vec<Import>> {
     let src = path.read_to_string()?;
     let ext = Path::new(path.as_str())
         .extension()
         .and_then(|s| s.to_str())
         .unwrap_or("");
-    let module = parse_module(&src, ext, FileName::Custom(path.as_str().into()))?;
-    let mut imports = collect_imports(&module);
-
-    static REQUIRE_RE: OnceLock<Regex> = OnceLock::new();
-    let re = REQUIRE_RE.get_or_init(|| Regex::new(r#"require\(\s*['\"]([^'\"]+)['\"]\s*\)"#).expect("invalid regex"));
+    parse_imports(&src, ext, FileName::Custom(path.as_str().into()))
+}
 
-    for cap in re.captures_iter(&src) {
-        imports.push(cap[1].to_string());
-    }
-    Ok(imports)
+/// Collect import specifiers from anywhere in a parsed module, in source order.
+pub fn collect_imports(module: &Module) -> Vec<Import> {
+    let mut collector = ImportCollector::default();
+    module.visit_with(&mut collector);
+    collector.imports
 }
 
-/// Collect import specifiers from a parsed module.
-pub fn collect_imports(module: &Module) -> Vec<String> {
-    let mut imports = Vec::new();
+/// Collect the names a module exports from its own declarations. Re-exports
+/// (`export ... from`) are reported by [`collect_imports`] instead.
+pub fn collect_exports(module: &Module) -> Vec<Export> {
+    let mut exports = Vec::new();
     for item in &module.body {
-        if let ModuleItem::ModuleDecl(decl) = item {
-            match decl {
-                ModuleDecl::Import(import) => {
-                    imports.push(import.src.value.to_string());
-                }
-                ModuleDecl::ExportAll(export) => {
-                    imports.push(export.src.value.to_string());
+        let ModuleItem::ModuleDecl(decl) = item else {
+            continue;
+        };
+        match decl {
+            ModuleDecl::ExportDecl(export) => {
+                for (name, span) in decl_names(&export.decl) {
+                    exports.push(Export { name, span });
                 }
-                ModuleDecl::ExportNamed(named) => {
-                    if let Some(src) = &named.src {
-                        imports.push(src.value.to_string());
+            }
+            ModuleDecl::ExportDefaultDecl(export) => exports.push(Export {
+                name: "default".to_string(),
+                span: export.span,
+            }),
+            ModuleDecl::ExportDefaultExpr(export) => exports.push(Export {
+                name: "default".to_string(),
+                span: export.span,
+            }),
+            // `export = x` is what `import x from` and `require` give
+            ModuleDecl::TsExportAssignment(export) => exports.push(Export {
+                name: "default".to_string(),
+                span: export.span,
+            }),
+            // `export as namespace X` only exposes the exports as a UMD
+            // global, it adds no name to import
+            ModuleDecl::TsNamespaceExport(_) => {}
+            ModuleDecl::ExportNamed(named) if named.src.is_none() => {
+                for spec in &named.specifiers {
+                    if let ExportSpecifier::Named(spec) = spec {
+                        let exported = spec.exported.as_ref().unwrap_or(&spec.orig);
+                        exports.push(Export {
+                            name: export_name(exported),
+                            span: exported.span(),
+                        });
                     }
                 }
-                _ => {}
             }
+            _ => {}
+        }
+    }
+    exports
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(s) => s.value.to_string(),
+    }
+}
+
+/// Names bound by an exported declaration.
+fn decl_names(decl: &Decl) -> Vec<(String, Span)> {
+    let mut names = Vec::new();
+    match decl {
+        Decl::Class(c) => names.push((c.ident.sym.to_string(), c.ident.span)),
+        Decl::Fn(f) => names.push((f.ident.sym.to_string(), f.ident.span)),
+        Decl::Var(v) => {
+            for d in &v.decls {
+                pat_names(&d.name, &mut names);
+            }
+        }
+        Decl::TsInterface(i) => names.push((i.id.sym.to_string(), i.id.span)),
+        Decl::TsTypeAlias(t) => names.push((t.id.sym.to_string(), t.id.span)),
+        Decl::TsEnum(e) => names.push((e.id.sym.to_string(), e.id.span)),
+        Decl::TsModule(m) => {
+            if let TsModuleName::Ident(id) = &m.id {
+                names.push((id.sym.to_string(), id.span));
+            }
+        }
+        Decl::Using(_) => {}
+    }
+    names
+}
+
+fn pat_names(pat: &Pat, names: &mut Vec<(String, Span)>) {
+    match pat {
+        Pat::Ident(b) => names.push((b.id.sym.to_string(), b.id.span)),
+        Pat::Array(a) => {
+            for elem in a.elems.iter().flatten() {
+                pat_names(elem, names);
+            }
+        }
+        Pat::Object(o) => {
+            for prop in &o.props {
+                match prop {
+                    ObjectPatProp::KeyValue(kv) => pat_names(&kv.value, names),
+                    ObjectPatProp::Assign(a) => names.push((a.key.sym.to_string(), a.key.span)),
+                    ObjectPatProp::Rest(r) => pat_names(&r.arg, names),
+                }
+            }
+        }
+        Pat::Assign(a) => pat_names(&a.left, names),
+        Pat::Rest(r) => pat_names(&r.arg, names),
+        Pat::Invalid(_) | Pat::Expr(_) => {}
+    }
+}
+
+/// Visitor that records every module reference in the AST.
+#[derive(Default)]
+struct ImportCollector {
+    imports: Vec<Import>,
+}
+
+impl ImportCollector {
+    fn push(&mut self, src: &Str, kind: ImportKind, bindings: Vec<Binding>) {
+        self.imports.push(Import {
+            specifier: src.value.to_string(),
+            kind,
+            span: src.span,
+            bindings,
+        });
+    }
+}
+
+/// Return the string value of a call argument if it is a literal specifier.
+fn literal_arg(args: &[ExprOrSpread]) -> Option<(String, Span)> {
+    let arg = args.first()?;
+    if arg.spread.is_some() {
+        return None;
+    }
+    match &*arg.expr {
+        Expr::Lit(Lit::Str(s)) => Some((s.value.to_string(), s.span)),
+        Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+            let quasi = tpl.quasis.first()?;
+            let cooked = quasi.cooked.as_ref()?;
+            Some((cooked.to_string(), tpl.span))
+        }
+        _ => None,
+    }
+}
+
+impl Visit for ImportCollector {
+    fn visit_import_decl(&mut self, import: &ImportDecl) {
+        let all_types = !import.specifiers.is_empty()
+            && import.specifiers.iter().all(|s| {
+                matches!(s, ImportSpecifier::Named(named) if named.is_type_only)
+            });
+        let kind = if import.type_only || all_types {
+            ImportKind::Type
+        } else if import.specifiers.is_empty() {
+            ImportKind::SideEffect
+        } else {
+            ImportKind::Static
+        };
+        let bindings = import
+            .specifiers
+            .iter()
+            .map(|spec| match spec {
+                ImportSpecifier::Default(d) => Binding::new("default", &*d.local.sym),
+                ImportSpecifier::Namespace(n) => Binding::new("*", &*n.local.sym),
+                ImportSpecifier::Named(n) => {
+                    let imported = n
+                        .imported
+                        .as_ref()
+                        .map(export_name)
+                        .unwrap_or_else(|| n.local.sym.to_string());
+                    Binding::new(imported, &*n.local.sym)
+                }
+            })
+            .collect();
+        self.push(&import.src, kind, bindings);
+    }
+
+    fn visit_export_all(&mut self, export: &ExportAll) {
+        let kind = if export.type_only {
+            ImportKind::TypeReExport
+        } else {
+            ImportKind::ReExport
+        };
+        self.push(&export.src, kind, vec![Binding::namespace()]);
+    }
+
+    fn visit_named_export(&mut self, named: &NamedExport) {
+        if let Some(src) = &named.src {
+            let all_types = !named.specifiers.is_empty()
+                && named.specifiers.iter().all(|s| {
+                    matches!(s, ExportSpecifier::Named(spec) if spec.is_type_only)
+                });
+            let kind = if named.type_only || all_types {
+                ImportKind::TypeReExport
+            } else {
+                ImportKind::ReExport
+            };
+            let bindings = named
+                .specifiers
+                .iter()
+                .map(|spec| match spec {
+                    ExportSpecifier::Named(n) => Binding::new(
+                        export_name(&n.orig),
+                        export_name(n.exported.as_ref().unwrap_or(&n.orig)),
+                    ),
+                    ExportSpecifier::Namespace(n) => Binding::new("*", export_name(&n.name)),
+                    ExportSpecifier::Default(d) => Binding::new("default", &*d.exported.sym),
+                })
+                .collect();
+            self.push(src, kind, bindings);
+        }
+    }
+
+    fn visit_ts_import_type(&mut self, import: &TsImportType) {
+        let binding = match &import.qualifier {
+            Some(TsEntityName::Ident(ident)) => Binding::new(&*ident.sym, &*ident.sym),
+            _ => Binding::namespace(),
+        };
+        self.push(&import.arg, ImportKind::Type, vec![binding]);
+        import.visit_children_with(self);
+    }
+
+    fn visit_ts_import_equals_decl(&mut self, decl: &TsImportEqualsDecl) {
+        if let TsModuleRef::TsExternalModuleRef(r) = &decl.module_ref {
+            let kind = if decl.is_type_only {
+                ImportKind::Type
+            } else {
+                ImportKind::Require
+            };
+            self.push(&r.expr, kind, vec![Binding::new("*", &*decl.id.sym)]);
+        }
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        let kind = match &call.callee {
+            Callee::Import(_) => Some(ImportKind::Dynamic),
+            Callee::Expr(callee) => match &**callee {
+                Expr::Ident(ident) if &*ident.sym == "require" => Some(ImportKind::Require),
+                Expr::Member(MemberExpr {
+                    obj,
+                    prop: MemberProp::Ident(prop),
+                    ..
+                }) if &*prop.sym == "resolve"
+                    && matches!(&**obj, Expr::Ident(o) if &*o.sym == "require") =>
+                {
+                    Some(ImportKind::RequireResolve)
+                }
+                _ => None,
+            },
+            Callee::Super(_) => None,
+        };
+        if let Some(kind) = kind
+            && let Some((specifier, span)) = literal_arg(&call.args)
+        {
+            self.imports.push(Import {
+                specifier,
+                kind,
+                span,
+                bindings: vec![Binding::namespace()],
+            });
         }
+        call.visit_children_with(self);
     }
-    imports
 }
 
 pub struct JsParser;
@@ -89,69 +480,95 @@ impl Parser for JsParser {
     }
 
     fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
-        let root_str = ctx.root.as_str().trim_end_matches('/');
-        let rel = path
+        let src = path.read_to_string()?;
+        let ext = Path::new(path.as_str())
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("");
+        let info = parse_source(&src, ext, FileName::Custom(path.as_str().into()))?;
+        Ok(module_edges(path, &src, info, ctx))
+    }
+}
+
+/// Resolve the imports and exports of a module at `path` into graph edges.
+/// `src` is the text the spans in `info` point into.
+pub fn module_edges(path: &VfsPath, src: &str, info: ModuleInfo, ctx: &Context) -> Vec<Edge> {
+    let root_str = ctx.root.as_str().trim_end_matches('/');
+    let rel = path
+        .as_str()
+        .strip_prefix(root_str)
+        .unwrap_or(path.as_str())
+        .trim_start_matches('/');
+    let mut edges = Vec::new();
+    for export in info.exports {
+        edges.push(Edge {
+            from: rel.to_string(),
+            to: symbol_node_name(rel, &export.name),
+            kind: EdgeType::Export,
+            from_type: None,
+            to_type: Some(NodeKind::Symbol),
+            location: Some(Location::from_offset(rel, src, export.span.lo.0 as usize)),
+            ..Default::default()
+        });
+    }
+    let file_target = |target: VfsPath| {
+        let rel = target
             .as_str()
             .strip_prefix(root_str)
-            .unwrap_or(path.as_str())
-            .trim_start_matches('/');
-        let imports = parse_file(path, ctx.logger)?;
-        let mut edges = Vec::new();
-        let dir = path.parent();
-        for i in imports {
-            let (target_str, to_type) = if i.starts_with('.') {
-                if let Some(target) = resolve_relative_import(&dir, &i) {
-                    let rel = target
-                        .as_str()
-                        .strip_prefix(root_str)
-                        .unwrap_or(target.as_str())
-                        .trim_start_matches('/')
-                        .to_string();
-                    let ext = Path::new(target.as_str())
-                        .extension()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("");
-                    let to_type = if JS_EXTENSIONS.contains(&ext) {
-                        None
-                    } else {
-                        Some(NodeKind::Asset)
-                    };
-                    (rel, to_type)
-                } else {
-                    continue;
-                }
-            } else if let Some(target) = resolve_alias_import(ctx.aliases, &i) {
-                let rel = target
-                    .as_str()
-                    .strip_prefix(root_str)
-                    .unwrap_or(target.as_str())
-                    .trim_start_matches('/')
-                    .to_string();
-                let ext = Path::new(target.as_str())
-                    .extension()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("");
-                let to_type = if JS_EXTENSIONS.contains(&ext) {
+            .unwrap_or(target.as_str())
+            .trim_start_matches('/')
+            .to_string();
+        let ext = Path::new(target.as_str())
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("");
+        let to_type = if is_module_extension(ext) {
+            None
+        } else {
+            Some(NodeKind::Asset)
+        };
+        (rel, to_type)
+    };
+    for import in info.imports {
+        let mut spec = import.specifier.clone();
+        let target = if spec.starts_with('.') {
+            resolve_ts_relative_import(ctx.tsconfigs, path, &spec)
+        } else if let Some(target) = resolve_import_map(ctx.root, ctx.import_maps, path, &spec) {
+            Some(target)
+        } else if let Some(target) = resolve_alias_import(ctx.tsconfigs, path, &spec) {
+            Some(target)
+        } else {
+            match resolve_package_import(ctx.packages, path, &spec, ctx.conditions) {
+                Some(PackageTarget::File(target)) => Some(target),
+                Some(PackageTarget::Bare(bare)) => {
+                    spec = bare;
                     None
-                } else {
-                    Some(NodeKind::Asset)
-                };
-                (rel, to_type)
-            } else if is_node_builtin(&i) {
-                (i.clone(), Some(NodeKind::Builtin))
-            } else {
-                (i.clone(), Some(NodeKind::External))
-            };
-            edges.push(Edge {
-                from: rel.to_string(),
-                to: target_str,
-                kind: EdgeType::Regular,
-                from_type: None,
-                to_type,
-            });
-        }
-        Ok(edges)
+                }
+                None => None,
+            }
+        };
+        let (target_str, to_type) = match target {
+            Some(target) => file_target(target),
+            None if spec.starts_with('.') || spec.starts_with('#') => {
+                (unresolved_target(ctx.root, path, &spec), Some(NodeKind::Unresolved))
+            }
+            None if is_node_builtin(&spec) => (spec, Some(NodeKind::Builtin)),
+            None => (spec, Some(NodeKind::External)),
+        };
+        edges.push(Edge {
+            from: rel.to_string(),
+            to: target_str,
+            kind: EdgeType::Regular,
+            from_type: None,
+            to_type,
+            import_kind: Some(import.kind),
+            location: Some(Location::from_offset(rel, src, import.span.lo.0 as usize)),
+            symbols: import.bindings,
+            specifier: Some(import.specifier),
+            ..Default::default()
+        });
     }
+    edges
 }
 
 #[cfg(test)]
@@ -177,11 +594,154 @@ mod tests {
         let module = parse_module(src, "js", FileName::Custom("test.js".into())).unwrap();
         let imports = collect_imports(&module);
         assert_eq!(
-            imports,
-            vec!["./foo", "./bar", "./baz.js"]
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
+            imports.iter().map(|i| i.specifier.as_str()).collect::<Vec<_>>(),
+            ["./foo", "./bar", "./baz.js"]
+        );
+        assert_eq!(
+            imports.iter().map(|i| i.kind).collect::<Vec<_>>(),
+            [ImportKind::Static, ImportKind::ReExport, ImportKind::ReExport]
+        );
+    }
+
+    #[test]
+    fn test_parse_imports_kinds_and_locations() {
+        let src = "import type { T } from './t';\nimport './side.css';\n  const a = require('./a');";
+        let imports = parse_imports(src, "ts", FileName::Custom("test.ts".into())).unwrap();
+        let found: Vec<_> = imports
+            .iter()
+            .map(|i| {
+                let loc = Location::from_offset("test.ts", src, i.span.lo.0 as usize);
+                (i.specifier.as_str(), i.kind, loc.line, loc.column)
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("./t", ImportKind::Type, 1, 24),
+                ("./side.css", ImportKind::SideEffect, 2, 8),
+                ("./a", ImportKind::Require, 3, 21),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_nested_and_dynamic_imports() {
+        let src = r#"
+            // require('./commented');
+            const s = "require('./in-string')";
+            function load() {
+                if (cond) {
+                    return require('./nested');
+                }
+                return import('./lazy');
+            }
+            const p = require.resolve('./resolved');
+            const t = import(`./template`);
+        "#;
+        let module = parse_module(src, "js", FileName::Custom("test.js".into())).unwrap();
+        let imports = collect_imports(&module);
+        let found: Vec<_> = imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("./nested", ImportKind::Require),
+                ("./lazy", ImportKind::Dynamic),
+                ("./resolved", ImportKind::RequireResolve),
+                ("./template", ImportKind::Dynamic),
+            ]
+        );
+        assert!(imports[1].kind.is_lazy());
+        assert!(!imports[0].kind.is_lazy());
+    }
+
+    #[test]
+    fn test_collect_ts_import_equals_and_export_assignment() {
+        let src = "import fs = require('./fs');\nimport type T = require('./t');\nexport = require('./impl');";
+        let module = parse_module(src, "ts", FileName::Custom("test.ts".into())).unwrap();
+        let found: Vec<_> = collect_imports(&module)
+            .into_iter()
+            .map(|i| (i.specifier, i.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("./fs".to_string(), ImportKind::Require),
+                ("./t".to_string(), ImportKind::Type),
+                ("./impl".to_string(), ImportKind::Require),
+            ]
+        );
+        let exports: Vec<_> = collect_exports(&module).into_iter().map(|e| e.name).collect();
+        assert_eq!(exports, ["default"]);
+
+        let src = "const api = {};\nexport = api;\nexport as namespace Api;";
+        let module = parse_module(src, "ts", FileName::Custom("test.ts".into())).unwrap();
+        let exports: Vec<_> = collect_exports(&module).into_iter().map(|e| e.name).collect();
+        assert_eq!(exports, ["default"]);
+    }
+
+    #[test]
+    fn test_collect_type_only_imports() {
+        let src = "import type { A } from './a';\n\
+                   import { type B, type C } from './b';\n\
+                   import { type D, e } from './d';\n\
+                   export type { F } from './f';\n\
+                   export { type G } from './g';\n\
+                   export type * from './h';\n\
+                   let x: typeof import('./i');";
+        let module = parse_module(src, "ts", FileName::Custom("test.ts".into())).unwrap();
+        let found: Vec<_> = collect_imports(&module)
+            .into_iter()
+            .map(|i| (i.specifier, i.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("./a".to_string(), ImportKind::Type),
+                ("./b".to_string(), ImportKind::Type),
+                ("./d".to_string(), ImportKind::Static),
+                ("./f".to_string(), ImportKind::TypeReExport),
+                ("./g".to_string(), ImportKind::TypeReExport),
+                ("./h".to_string(), ImportKind::TypeReExport),
+                ("./i".to_string(), ImportKind::Type),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_bindings_and_exports() {
+        let src = "import def, { a, b as c } from './x';\n\
+                   import * as ns from './y';\n\
+                   export { d as e, default as f } from './z';\n\
+                   export * as all from './w';\n\
+                   export function g() {}\n\
+                   export const { h, i: [j] } = obj;\n\
+                   const k = 1;\n\
+                   export { k as l };\n\
+                   export default k;";
+        let info = parse_source(src, "js", FileName::Custom("test.js".into())).unwrap();
+        let bindings: Vec<_> = info
+            .imports
+            .iter()
+            .map(|i| {
+                i.bindings
+                    .iter()
+                    .map(|b| format!("{}:{}", b.imported, b.local))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(
+            bindings,
+            [
+                vec!["default:def", "a:a", "b:c"],
+                vec!["*:ns"],
+                vec!["d:e", "default:f"],
+                vec!["*:all"],
+            ]
         );
+        let exports: Vec<_> = info.exports.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(exports, ["g", "h", "j", "l", "default"]);
     }
 }
@@ -0,0 +1,196 @@
+pub mod npm;
+pub mod pnpm;
+pub mod yarn;
+
+use vfs::VfsPath;
+
+use dep_core::{Context, Dependency, DependencyKind, Edge, EdgeType, NodeKind, Parser};
+
+/// Lockfiles understood by [`LockfileParser`].
+pub const LOCKFILES: &[&str] = &["pnpm-lock.yaml", "package-lock.json", "yarn.lock"];
+
+/// A third-party package version installed by a lockfile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<LockedDependency>,
+}
+
+/// A dependency resolved to the version the lockfile installs for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockedDependency {
+    pub name: String,
+    pub version: String,
+    pub kind: DependencyKind,
+}
+
+/// A project package (the root or a workspace member) recorded in a
+/// lockfile, with the versions installed for its direct dependencies.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockedImporter {
+    /// Folder of the package, relative to the lockfile's folder
+    pub dir: String,
+    pub dependencies: Vec<LockedDependency>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lockfile {
+    pub importers: Vec<LockedImporter>,
+    pub packages: Vec<LockedPackage>,
+}
+
+/// Node name of an installed package version.
+pub fn package_id(name: &str, version: &str) -> String {
+    format!("{name}@{version}")
+}
+
+/// Split `name@version` (or `name@range`) at the `@` after the name, which
+/// may be scoped.
+pub(crate) fn split_at_version(spec: &str) -> Option<(&str, &str)> {
+    let at = spec.get(1..)?.find('@')? + 1;
+    Some((&spec[..at], &spec[at + 1..]))
+}
+
+/// Parse a lockfile by its file name.
+pub fn parse_lockfile(path: &VfsPath) -> anyhow::Result<Lockfile> {
+    let contents = path.read_to_string()?;
+    match path.filename().as_str() {
+        "pnpm-lock.yaml" => pnpm::parse(&contents),
+        "package-lock.json" => npm::parse(&contents),
+        "yarn.lock" => yarn::parse(&contents),
+        name => anyhow::bail!("{name} is not a known lockfile"),
+    }
+}
+
+/// Adds the installed versions of third-party packages from `pnpm-lock.yaml`,
+/// `package-lock.json` and `yarn.lock`. Each package links to its installed
+/// versions with `Resolved` edges, and each version to the versions of its
+/// own dependencies, building the transitive tree. Project packages link to
+/// the versions installed for their direct dependencies.
+pub struct LockfileParser;
+
+impl Parser for LockfileParser {
+    fn name(&self) -> &'static str {
+        "lockfile"
+    }
+    fn can_parse(&self, path: &VfsPath) -> bool {
+        LOCKFILES.contains(&path.filename().as_str())
+    }
+
+    fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
+        let lockfile = parse_lockfile(path)?;
+        let mut edges = Vec::new();
+        let edge = |from: String, to: String, kind: EdgeType, dependency: Option<Dependency>| Edge {
+            from,
+            to,
+            kind,
+            from_type: None,
+            to_type: Some(NodeKind::External),
+            dependency,
+            ..Default::default()
+        };
+        let lock_dir = path.parent();
+        for importer in &lockfile.importers {
+            let dir = match importer.dir.trim_start_matches("./") {
+                "" | "." => lock_dir.clone(),
+                dir => lock_dir.join(dir)?,
+            };
+            let Some(name) = ctx
+                .packages
+                .iter()
+                .find(|p| p.dir == dir)
+                .and_then(|p| p.name.clone())
+            else {
+                continue;
+            };
+            for dep in &importer.dependencies {
+                edges.push(edge(
+                    name.clone(),
+                    package_id(&dep.name, &dep.version),
+                    EdgeType::Resolved,
+                    None,
+                ));
+            }
+        }
+        for package in &lockfile.packages {
+            let id = package_id(&package.name, &package.version);
+            edges.push(Edge {
+                from_type: Some(NodeKind::External),
+                ..edge(package.name.clone(), id.clone(), EdgeType::Resolved, None)
+            });
+            for dep in &package.dependencies {
+                edges.push(edge(
+                    id.clone(),
+                    package_id(&dep.name, &dep.version),
+                    EdgeType::Regular,
+                    Some(Dependency {
+                        kind: dep.kind,
+                        version: dep.version.clone(),
+                    }),
+                ));
+            }
+        }
+        Ok(edges)
+    }
+}
+
+/// Sort packages and drop repeated versions, which lockfiles list once per
+/// set of peer dependencies or per patch.
+pub(crate) fn dedup_packages(packages: &mut Vec<LockedPackage>) {
+    packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    packages.dedup_by(|a, b| {
+        if a.name != b.name || a.version != b.version {
+            return false;
+        }
+        for dep in a.dependencies.drain(..) {
+            if !b.dependencies.contains(&dep) {
+                b.dependencies.push(dep);
+            }
+        }
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::package_json::PackageManifest;
+    use dep_core::test_util::{TestFS, test_context};
+
+    #[test]
+    fn test_lockfile_edges() {
+        let fs = TestFS::new([
+            ("package.json", r#"{"name":"app","dependencies":{"debug":"^4.0.0"}}"#),
+            (
+                "pnpm-lock.yaml",
+                "lockfileVersion: '9.0'\n\
+                 importers:\n  .:\n    dependencies:\n      debug:\n        specifier: ^4.0.0\n        version: 4.3.4\n\
+                 packages:\n  debug@4.3.4:\n    resolution: {integrity: sha512-x}\n  ms@2.1.2:\n    resolution: {integrity: sha512-y}\n\
+                 snapshots:\n  debug@4.3.4:\n    dependencies:\n      ms: 2.1.2\n  ms@2.1.2: {}\n",
+            ),
+        ]);
+        let root = fs.root();
+        let packages = [PackageManifest::load(&root.join("package.json").unwrap()).unwrap().unwrap()];
+        let ctx = Context {
+            packages: &packages,
+            ..test_context(&root)
+        };
+        let edges = LockfileParser
+            .parse(&root.join("pnpm-lock.yaml").unwrap(), &ctx)
+            .unwrap();
+        let edges: Vec<_> = edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.kind.clone()))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("app", "debug@4.3.4", EdgeType::Resolved),
+                ("debug", "debug@4.3.4", EdgeType::Resolved),
+                ("debug@4.3.4", "ms@2.1.2", EdgeType::Regular),
+                ("ms", "ms@2.1.2", EdgeType::Resolved),
+            ]
+        );
+    }
+}
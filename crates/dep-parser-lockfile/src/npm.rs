@@ -0,0 +1,236 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+use dep_core::DependencyKind;
+
+use crate::{LockedDependency, LockedImporter, LockedPackage, Lockfile, dedup_packages};
+
+#[derive(Deserialize)]
+struct PackageLock {
+    packages: Option<BTreeMap<String, Entry>>,
+    dependencies: Option<BTreeMap<String, V1Entry>>,
+}
+
+/// Entry of the `packages` map of lockfile versions 2 and 3, keyed by the
+/// install folder: `""` for the root, `node_modules/a/node_modules/b` for
+/// installed packages, and the folder of each workspace member.
+#[derive(Deserialize)]
+struct Entry {
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    link: bool,
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    #[serde(default, rename = "devDependencies")]
+    dev_dependencies: HashMap<String, String>,
+    #[serde(default, rename = "optionalDependencies")]
+    optional_dependencies: HashMap<String, String>,
+    #[serde(default, rename = "peerDependencies")]
+    peer_dependencies: HashMap<String, String>,
+}
+
+/// Entry of the nested `dependencies` tree of lockfile version 1.
+#[derive(Deserialize)]
+struct V1Entry {
+    version: String,
+    #[serde(default)]
+    requires: HashMap<String, String>,
+    dependencies: Option<BTreeMap<String, V1Entry>>,
+}
+
+/// Parse a `package-lock.json`, from its `packages` map when present
+/// (versions 2 and 3), or from the nested `dependencies` tree (version 1).
+pub fn parse(contents: &str) -> anyhow::Result<Lockfile> {
+    let lock: PackageLock = serde_json::from_str(contents)?;
+    let mut lockfile = Lockfile::default();
+    if let Some(packages) = &lock.packages {
+        for (key, entry) in packages {
+            let deps = [
+                (&entry.dependencies, DependencyKind::Prod),
+                (&entry.dev_dependencies, DependencyKind::Dev),
+                (&entry.optional_dependencies, DependencyKind::Optional),
+                (&entry.peer_dependencies, DependencyKind::Peer),
+            ];
+            let mut dependencies: Vec<LockedDependency> = Vec::new();
+            for (map, kind) in deps {
+                let mut names: Vec<&String> = map.keys().collect();
+                names.sort();
+                for name in names {
+                    let Some(installed) = find_installed(packages, key, name) else {
+                        continue;
+                    };
+                    let Some(version) = &installed.version else {
+                        continue;
+                    };
+                    if installed.link {
+                        continue;
+                    }
+                    dependencies.push(LockedDependency {
+                        name: installed.name.clone().unwrap_or_else(|| name.clone()),
+                        version: version.clone(),
+                        kind,
+                    });
+                }
+            }
+            match key.rsplit_once("node_modules/") {
+                Some((_, name)) => {
+                    let Some(version) = &entry.version else {
+                        continue;
+                    };
+                    if entry.link {
+                        continue;
+                    }
+                    lockfile.packages.push(LockedPackage {
+                        // Aliased installs record the real name
+                        name: entry.name.clone().unwrap_or_else(|| name.to_string()),
+                        version: version.clone(),
+                        dependencies,
+                    });
+                }
+                None => lockfile.importers.push(LockedImporter {
+                    dir: key.clone(),
+                    dependencies,
+                }),
+            }
+        }
+    } else if let Some(deps) = &lock.dependencies {
+        walk_v1(deps, &mut Vec::new(), &mut lockfile.packages);
+    }
+    dedup_packages(&mut lockfile.packages);
+    Ok(lockfile)
+}
+
+/// Find the package Node would load for `name` from the package installed
+/// at `from`: in its own `node_modules`, then in those of each enclosing
+/// folder.
+fn find_installed<'a>(packages: &'a BTreeMap<String, Entry>, from: &str, name: &str) -> Option<&'a Entry> {
+    let mut base = from;
+    loop {
+        let key = if base.is_empty() {
+            format!("node_modules/{name}")
+        } else {
+            format!("{base}/node_modules/{name}")
+        };
+        if let Some(entry) = packages.get(&key) {
+            return Some(entry);
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = match base.rfind("/node_modules/") {
+            Some(i) => &base[..i],
+            None => "",
+        };
+    }
+}
+
+/// Collect the packages of a version 1 tree. `scopes` holds the enclosing
+/// `dependencies` maps, searched innermost first to resolve `requires`.
+fn walk_v1<'a>(
+    deps: &'a BTreeMap<String, V1Entry>,
+    scopes: &mut Vec<&'a BTreeMap<String, V1Entry>>,
+    out: &mut Vec<LockedPackage>,
+) {
+    scopes.push(deps);
+    for (name, entry) in deps {
+        // Workspace members and local folders are not third-party packages
+        if entry.version.starts_with("file:") {
+            continue;
+        }
+        let mut requires: Vec<_> = entry.requires.keys().collect();
+        requires.sort();
+        let dependencies = requires
+            .into_iter()
+            .filter_map(|dep| {
+                let installed = entry
+                    .dependencies
+                    .iter()
+                    .chain(scopes.iter().rev().copied())
+                    .find_map(|scope| scope.get(dep))?;
+                Some(LockedDependency {
+                    name: dep.clone(),
+                    version: installed.version.clone(),
+                    kind: DependencyKind::Prod,
+                })
+            })
+            .collect();
+        out.push(LockedPackage {
+            name: name.clone(),
+            version: entry.version.clone(),
+            dependencies,
+        });
+        if let Some(nested) = &entry.dependencies {
+            walk_v1(nested, scopes, out);
+        }
+    }
+    scopes.pop();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npm_lockfile_versions() {
+        let v3 = parse(
+            r#"{"lockfileVersion":3,"packages":{
+                "":{"name":"root","workspaces":["packages/*"],"dependencies":{"debug":"^4.0.0"}},
+                "packages/app":{"name":"app","dependencies":{"debug":"^2.0.0","ui":"*"}},
+                "node_modules/app":{"resolved":"packages/app","link":true},
+                "node_modules/ui":{"resolved":"packages/ui","link":true},
+                "node_modules/debug":{"version":"4.3.4","dependencies":{"ms":"2.1.2"}},
+                "node_modules/ms":{"version":"2.1.2"},
+                "packages/app/node_modules/debug":{"version":"2.6.9","dependencies":{"ms":"2.0.0"}},
+                "packages/app/node_modules/ms":{"version":"2.0.0"}
+            }}"#,
+        )
+        .unwrap();
+        let ids = |deps: &[LockedDependency]| -> Vec<String> {
+            deps.iter().map(|d| format!("{}@{}", d.name, d.version)).collect()
+        };
+        let importers: Vec<_> = v3.importers.iter().map(|i| (i.dir.as_str(), ids(&i.dependencies))).collect();
+        assert_eq!(
+            importers,
+            [("", vec!["debug@4.3.4".to_string()]), ("packages/app", vec!["debug@2.6.9".to_string()])]
+        );
+        let packages: Vec<_> = v3
+            .packages
+            .iter()
+            .map(|p| (format!("{}@{}", p.name, p.version), ids(&p.dependencies)))
+            .collect();
+        assert_eq!(
+            packages,
+            [
+                ("debug@2.6.9".to_string(), vec!["ms@2.0.0".to_string()]),
+                ("debug@4.3.4".to_string(), vec!["ms@2.1.2".to_string()]),
+                ("ms@2.0.0".to_string(), vec![]),
+                ("ms@2.1.2".to_string(), vec![]),
+            ]
+        );
+
+        let v1 = parse(
+            r#"{"lockfileVersion":1,"dependencies":{
+                "debug":{"version":"2.6.9","requires":{"ms":"2.0.0"},
+                    "dependencies":{"ms":{"version":"2.0.0"}}},
+                "ms":{"version":"2.1.2"},
+                "send":{"version":"0.18.0","requires":{"ms":"2.1.2"}}
+            }}"#,
+        )
+        .unwrap();
+        let packages: Vec<_> = v1
+            .packages
+            .iter()
+            .map(|p| (format!("{}@{}", p.name, p.version), ids(&p.dependencies)))
+            .collect();
+        assert_eq!(
+            packages,
+            [
+                ("debug@2.6.9".to_string(), vec!["ms@2.0.0".to_string()]),
+                ("ms@2.0.0".to_string(), vec![]),
+                ("ms@2.1.2".to_string(), vec![]),
+                ("send@0.18.0".to_string(), vec!["ms@2.1.2".to_string()]),
+            ]
+        );
+    }
+}
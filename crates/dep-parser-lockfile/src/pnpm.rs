@@ -0,0 +1,208 @@
+use serde_yaml::Value;
+
+use dep_core::DependencyKind;
+
+use crate::{LockedDependency, LockedImporter, LockedPackage, Lockfile, dedup_packages};
+
+const FIELDS: &[(&str, DependencyKind)] = &[
+    ("dependencies", DependencyKind::Prod),
+    ("devDependencies", DependencyKind::Dev),
+    ("optionalDependencies", DependencyKind::Optional),
+];
+
+/// Parse a `pnpm-lock.yaml`. Version 9 lists dependencies under `snapshots`
+/// keyed `name@version`; versions 6 to 8 under `packages` keyed
+/// `/name@version`, and version 5 keyed `/name/version`.
+pub fn parse(contents: &str) -> anyhow::Result<Lockfile> {
+    let doc: Value = serde_yaml::from_str(contents)?;
+    let mut lockfile = Lockfile::default();
+
+    if let Some(importers) = doc.get("importers").and_then(Value::as_mapping) {
+        for (dir, importer) in importers {
+            let Some(dir) = dir.as_str() else {
+                continue;
+            };
+            lockfile.importers.push(LockedImporter {
+                dir: dir.to_string(),
+                dependencies: dependencies(importer),
+            });
+        }
+    } else if doc.get("dependencies").is_some() || doc.get("devDependencies").is_some() {
+        // Single-package lockfiles keep the root's dependencies at the top
+        lockfile.importers.push(LockedImporter {
+            dir: ".".to_string(),
+            dependencies: dependencies(&doc),
+        });
+    }
+
+    let entries = doc
+        .get("snapshots")
+        .or_else(|| doc.get("packages"))
+        .and_then(Value::as_mapping);
+    for (key, entry) in entries.into_iter().flatten() {
+        let Some((name, version)) = key.as_str().and_then(parse_key) else {
+            continue;
+        };
+        lockfile.packages.push(LockedPackage {
+            name,
+            version,
+            dependencies: dependencies(entry),
+        });
+    }
+    dedup_packages(&mut lockfile.packages);
+    Ok(lockfile)
+}
+
+/// Split a package key into name and version, dropping the peer dependency
+/// suffix: `(react@18.2.0)` since version 6, `_react@18.2.0` before.
+fn parse_key(key: &str) -> Option<(String, String)> {
+    let key = key.trim_start_matches('/');
+    let key = key.split('(').next()?;
+    let (scope, rest) = match key.strip_prefix('@') {
+        Some(scoped) => {
+            let (scope, rest) = scoped.split_once('/')?;
+            (format!("@{scope}/"), rest)
+        }
+        None => (String::new(), key),
+    };
+    let sep = rest.find(['/', '@'])?;
+    let version = &rest[sep + 1..];
+    let version = if rest[sep..].starts_with('/') {
+        version.split('_').next()?
+    } else {
+        version
+    };
+    Some((format!("{scope}{}", &rest[..sep]), version.to_string()))
+}
+
+/// Collect the dependencies of an importer or package entry. Importers of
+/// version 6 and later give `{ specifier, version }`, everything else the
+/// version itself. Links to local folders are skipped.
+fn dependencies(entry: &Value) -> Vec<LockedDependency> {
+    let mut deps = Vec::new();
+    for (field, kind) in FIELDS {
+        let Some(map) = entry.get(field).and_then(Value::as_mapping) else {
+            continue;
+        };
+        for (name, value) in map {
+            let version = value.get("version").unwrap_or(value);
+            let (Some(name), Some(version)) = (name.as_str(), version.as_str()) else {
+                continue;
+            };
+            if version.starts_with("link:") || version.starts_with("file:") {
+                continue;
+            }
+            let target = version.split('(').next().unwrap_or(version);
+            let (name, version) = if target.starts_with(|c: char| c.is_ascii_digit()) {
+                let version = target.split('_').next().unwrap_or(target);
+                (name.to_string(), version.to_string())
+            } else {
+                // Aliases name the installed package: `string-width@4.2.3`,
+                // or `/string-width@4.2.3` and `/string-width/4.2.3` before
+                // version 9
+                match parse_key(target) {
+                    Some(installed) => installed,
+                    None => continue,
+                }
+            };
+            deps.push(LockedDependency {
+                name,
+                version,
+                kind: *kind,
+            });
+        }
+    }
+    deps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(lockfile: &Lockfile) -> Vec<(String, Vec<String>)> {
+        lockfile
+            .packages
+            .iter()
+            .map(|p| {
+                let deps = p.dependencies.iter().map(|d| format!("{}@{}", d.name, d.version)).collect();
+                (format!("{}@{}", p.name, p.version), deps)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pnpm_lockfile_versions() {
+        let v9 = parse(
+            "lockfileVersion: '9.0'
+importers:
+  .:
+    dependencies:
+      react-dom:
+        specifier: ^18.2.0
+        version: 18.2.0(react@18.2.0)
+      ui:
+        specifier: workspace:*
+        version: link:packages/ui
+    devDependencies:
+      string-width-cjs:
+        specifier: npm:string-width@^4.2.0
+        version: string-width@4.2.3
+packages:
+  '@types/react@18.2.0':
+    resolution: {integrity: sha512-a}
+snapshots:
+  react-dom@18.2.0(react@18.2.0):
+    dependencies:
+      loose-envify: 1.4.0
+      react: 18.2.0
+  react-dom@18.2.0(react@18.3.0):
+    dependencies:
+      loose-envify: 1.4.0
+      react: 18.3.0
+  '@types/react@18.2.0': {}
+",
+        )
+        .unwrap();
+        assert_eq!(
+            v9.importers[0].dependencies,
+            [
+                LockedDependency { name: "react-dom".into(), version: "18.2.0".into(), kind: DependencyKind::Prod },
+                LockedDependency { name: "string-width".into(), version: "4.2.3".into(), kind: DependencyKind::Dev },
+            ]
+        );
+        assert_eq!(
+            versions(&v9),
+            [
+                ("@types/react@18.2.0".to_string(), vec![]),
+                (
+                    "react-dom@18.2.0".to_string(),
+                    vec!["loose-envify@1.4.0".to_string(), "react@18.2.0".to_string(), "react@18.3.0".to_string()]
+                ),
+            ]
+        );
+
+        let v5 = parse(
+            "lockfileVersion: 5.4
+specifiers:
+  react-dom: ^18.2.0
+dependencies:
+  react-dom: 18.2.0_react@18.2.0
+packages:
+  /react-dom/18.2.0_react@18.2.0:
+    dependencies:
+      loose-envify: 1.4.0
+  /@babel/core/7.24.0:
+    dev: true
+",
+        )
+        .unwrap();
+        assert_eq!(v5.importers[0].dependencies[0].version, "18.2.0");
+        assert_eq!(
+            versions(&v5),
+            [
+                ("@babel/core@7.24.0".to_string(), vec![]),
+                ("react-dom@18.2.0".to_string(), vec!["loose-envify@1.4.0".to_string()]),
+            ]
+        );
+    }
+}
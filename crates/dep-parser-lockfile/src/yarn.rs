@@ -0,0 +1,305 @@
+use serde_yaml::Value;
+use std::collections::HashMap;
+
+use dep_core::DependencyKind;
+
+use crate::{LockedDependency, LockedImporter, LockedPackage, Lockfile, dedup_packages, split_at_version};
+
+const FIELDS: &[(&str, DependencyKind)] = &[
+    ("dependencies", DependencyKind::Prod),
+    ("optionalDependencies", DependencyKind::Optional),
+    ("peerDependencies", DependencyKind::Peer),
+];
+
+/// One block of a `yarn.lock`: the descriptors (`name@range`) it resolves
+/// and what they resolve to.
+struct Block {
+    descriptors: Vec<String>,
+    version: String,
+    /// `(field, name, range)` for each dependency
+    dependencies: Vec<(DependencyKind, String, String)>,
+}
+
+/// Parse a `yarn.lock`, either the custom format of Yarn 1 or the YAML
+/// written by Yarn 2 and later (berry).
+pub fn parse(contents: &str) -> anyhow::Result<Lockfile> {
+    let blocks = if contents.contains("__metadata:") {
+        parse_berry(contents)?
+    } else {
+        parse_v1(contents)
+    };
+
+    let mut by_descriptor: HashMap<&str, &Block> = HashMap::new();
+    for block in &blocks {
+        for descriptor in &block.descriptors {
+            by_descriptor.insert(descriptor.as_str(), block);
+        }
+    }
+    let resolve = |name: &str, range: &str| {
+        by_descriptor
+            .get(format!("{name}@{range}").as_str())
+            // Berry leaves out the default `npm:` protocol in dependencies
+            .or_else(|| by_descriptor.get(format!("{name}@npm:{range}").as_str()))
+            .copied()
+    };
+
+    let mut lockfile = Lockfile::default();
+    for block in &blocks {
+        let mut dependencies = Vec::new();
+        for (kind, name, range) in &block.dependencies {
+            let Some(installed) = resolve(name, range) else {
+                continue;
+            };
+            if workspace_dir(installed).is_some() {
+                continue;
+            }
+            dependencies.push(LockedDependency {
+                name: package_name(installed).unwrap_or(name).to_string(),
+                version: installed.version.clone(),
+                kind: *kind,
+            });
+        }
+        if let Some(dir) = workspace_dir(block) {
+            lockfile.importers.push(LockedImporter {
+                dir: dir.to_string(),
+                dependencies,
+            });
+        } else if let Some(name) = package_name(block) {
+            lockfile.packages.push(LockedPackage {
+                name: name.to_string(),
+                version: block.version.clone(),
+                dependencies,
+            });
+        }
+    }
+    dedup_packages(&mut lockfile.packages);
+    Ok(lockfile)
+}
+
+/// Folder of a workspace member, from its `name@workspace:folder`
+/// descriptor. Other members refer to it as `workspace:*` or `workspace:^`.
+fn workspace_dir(block: &Block) -> Option<&str> {
+    block
+        .descriptors
+        .iter()
+        .filter_map(|d| split_at_version(d)?.1.strip_prefix("workspace:"))
+        .find(|dir| !matches!(*dir, "*" | "^" | "~"))
+}
+
+/// Name of the installed package. Aliases (`alias@npm:real@^1.0.0`) install
+/// the package named in the range.
+fn package_name(block: &Block) -> Option<&str> {
+    let (name, range) = split_at_version(block.descriptors.first()?)?;
+    match range.strip_prefix("npm:").and_then(split_at_version) {
+        Some((real, _)) => Some(real),
+        None => Some(name),
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim().trim_matches('"')
+}
+
+/// Parse the Yarn 1 format: unindented descriptor lines open a block, with
+/// `key value` fields and dependency sections indented below.
+fn parse_v1(contents: &str) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut section = None;
+    for line in contents.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim();
+        if indent == 0 {
+            let descriptors = line.trim_end_matches(':').split(", ").map(|d| unquote(d).to_string()).collect();
+            blocks.push(Block {
+                descriptors,
+                version: String::new(),
+                dependencies: Vec::new(),
+            });
+            section = None;
+            continue;
+        }
+        let Some(block) = blocks.last_mut() else {
+            continue;
+        };
+        if indent <= 2 {
+            section = line
+                .strip_suffix(':')
+                .and_then(|field| FIELDS.iter().find(|(f, _)| *f == field))
+                .map(|(_, kind)| *kind);
+            if let Some(version) = line.strip_prefix("version ") {
+                block.version = unquote(version).to_string();
+            }
+        } else if let Some(kind) = section
+            && let Some((name, range)) = line.split_once(' ')
+        {
+            block.dependencies.push((kind, unquote(name).to_string(), unquote(range).to_string()));
+        }
+    }
+    blocks
+}
+
+/// Parse the berry format, a YAML map from `"name@npm:range, ..."` keys to
+/// entries.
+fn parse_berry(contents: &str) -> anyhow::Result<Vec<Block>> {
+    let doc: Value = serde_yaml::from_str(contents)?;
+    let mut blocks = Vec::new();
+    for (key, entry) in doc.as_mapping().into_iter().flatten() {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        if key == "__metadata" {
+            continue;
+        }
+        let version = match entry.get("version") {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            _ => continue,
+        };
+        let mut dependencies = Vec::new();
+        for (field, kind) in FIELDS {
+            for (name, range) in entry.get(field).and_then(Value::as_mapping).into_iter().flatten() {
+                if let (Some(name), Some(range)) = (name.as_str(), range.as_str()) {
+                    dependencies.push((*kind, name.to_string(), range.to_string()));
+                }
+            }
+        }
+        blocks.push(Block {
+            descriptors: key.split(", ").map(|d| d.trim().to_string()).collect(),
+            version,
+            dependencies,
+        });
+    }
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Entries = Vec<(String, Vec<String>)>;
+
+    fn summary(lockfile: &Lockfile) -> (Entries, Entries) {
+        let ids = |deps: &[LockedDependency]| -> Vec<String> {
+            deps.iter().map(|d| format!("{}@{}", d.name, d.version)).collect()
+        };
+        (
+            lockfile.importers.iter().map(|i| (i.dir.clone(), ids(&i.dependencies))).collect(),
+            lockfile
+                .packages
+                .iter()
+                .map(|p| (format!("{}@{}", p.name, p.version), ids(&p.dependencies)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_yarn_v1_lockfile() {
+        let lockfile = parse(
+            r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/code-frame@^7.22.13", "@babel/code-frame@^7.23.5":
+  version "7.23.5"
+  resolved "https://registry.yarnpkg.com/@babel/code-frame/-/code-frame-7.23.5.tgz"
+  dependencies:
+    "@babel/highlight" "^7.23.4"
+    chalk "^2.4.2"
+
+"@babel/highlight@^7.23.4":
+  version "7.23.4"
+
+chalk@^2.4.2:
+  version "2.4.2"
+  optionalDependencies:
+    supports-color "^5.3.0"
+
+supports-color@^5.3.0:
+  version "5.5.0"
+"#,
+        )
+        .unwrap();
+        let (importers, packages) = summary(&lockfile);
+        assert!(importers.is_empty());
+        assert_eq!(
+            packages,
+            [
+                (
+                    "@babel/code-frame@7.23.5".to_string(),
+                    vec!["@babel/highlight@7.23.4".to_string(), "chalk@2.4.2".to_string()]
+                ),
+                ("@babel/highlight@7.23.4".to_string(), vec![]),
+                ("chalk@2.4.2".to_string(), vec!["supports-color@5.5.0".to_string()]),
+                ("supports-color@5.5.0".to_string(), vec![]),
+            ]
+        );
+        assert_eq!(lockfile.packages[2].dependencies[0].kind, DependencyKind::Optional);
+    }
+
+    #[test]
+    fn test_yarn_berry_lockfile() {
+        let lockfile = parse(
+            r#"# This file is generated by running "yarn install" inside your project.
+
+__metadata:
+  version: 8
+  cacheKey: 10c0
+
+"app@workspace:packages/app":
+  version: 0.0.0-use.local
+  resolution: "app@workspace:packages/app"
+  dependencies:
+    debug: "npm:^2.6.0"
+    ui: "workspace:*"
+  languageName: unknown
+  linkType: soft
+
+"debug@npm:^2.6.0":
+  version: 2.6.9
+  resolution: "debug@npm:2.6.9"
+  dependencies:
+    ms: "npm:2.0.0"
+  languageName: node
+  linkType: hard
+
+"debug@npm:^4.1.0, debug@npm:^4.3.4":
+  version: 4.3.4
+  resolution: "debug@npm:4.3.4"
+  dependencies:
+    ms: "npm:2.1.2"
+
+"ms@npm:2.0.0":
+  version: 2.0.0
+
+"ms@npm:2.1.2":
+  version: 2.1.2
+
+"ui@workspace:*, ui@workspace:packages/ui":
+  version: 0.0.0-use.local
+  dependencies:
+    debug: "npm:^4.1.0"
+"#,
+        )
+        .unwrap();
+        let (importers, packages) = summary(&lockfile);
+        assert_eq!(
+            importers,
+            [
+                ("packages/app".to_string(), vec!["debug@2.6.9".to_string()]),
+                ("packages/ui".to_string(), vec!["debug@4.3.4".to_string()]),
+            ]
+        );
+        assert_eq!(
+            packages,
+            [
+                ("debug@2.6.9".to_string(), vec!["ms@2.0.0".to_string()]),
+                ("debug@4.3.4".to_string(), vec!["ms@2.1.2".to_string()]),
+                ("ms@2.0.0".to_string(), vec![]),
+                ("ms@2.1.2".to_string(), vec![]),
+            ]
+        );
+    }
+}
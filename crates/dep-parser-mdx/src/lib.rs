@@ -1,14 +1,48 @@
-use regex::Regex;
 use std::path::Path;
-use std::sync::OnceLock;
 use vfs::VfsPath;
 
-use dep_core::js_resolve::{
-    JS_EXTENSIONS, is_node_builtin, resolve_alias_import, resolve_relative_import,
-};
 use dep_core::{Context, Edge, Parser};
-use dep_core::{EdgeType, NodeKind};
+use dep_parser_js::{module_edges, parse_source};
+use swc_common::FileName;
 
+/// Blank out everything in an MDX document except its top-level ESM blocks
+/// (paragraphs starting with `import` or `export`). Byte offsets and line
+/// breaks are preserved so spans still point into the original document.
+fn esm_source(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut in_esm = false;
+    let mut in_fence = false;
+    let mut paragraph_start = true;
+    for line in src.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            in_esm = false;
+        } else if trimmed.is_empty() {
+            in_esm = false;
+            paragraph_start = true;
+        } else if paragraph_start && !in_fence {
+            in_esm = trimmed.starts_with("import ") || trimmed.starts_with("export ");
+            paragraph_start = false;
+        }
+        if in_esm {
+            out.push_str(line);
+        } else {
+            for c in line.chars() {
+                if c == '\n' {
+                    out.push('\n');
+                } else {
+                    out.extend(std::iter::repeat_n(' ', c.len_utf8()));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Parses the ESM blocks of MDX documents with the JS parser, so the
+/// bindings a document imports and the names it exports are known to the
+/// symbol graph and to unused export detection like those of any module.
 pub struct MdxParser;
 
 impl Parser for MdxParser {
@@ -25,69 +59,8 @@ impl Parser for MdxParser {
 
     fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
         let src = path.read_to_string()?;
-        let root_str = ctx.root.as_str().trim_end_matches('/');
-        let rel = path
-            .as_str()
-            .strip_prefix(root_str)
-            .unwrap_or(path.as_str())
-            .trim_start_matches('/');
-        let mut edges = Vec::new();
-        static IMPORT_RE: OnceLock<Regex> = OnceLock::new();
-        let re = IMPORT_RE.get_or_init(|| Regex::new(r#"^\s*import\s+(?:[^'\"]*?from\s+)?['\"]([^'\"]+)['\"]"#).expect("invalid regex"));
-        let dir = path.parent();
-        for cap in re.captures_iter(&src) {
-            let spec = cap[1].to_string();
-            let (target_str, to_type) = if spec.starts_with('.') {
-                if let Some(target) = resolve_relative_import(&dir, &spec) {
-                    let target_rel = target
-                        .as_str()
-                        .strip_prefix(root_str)
-                        .unwrap_or(target.as_str())
-                        .trim_start_matches('/')
-                        .to_string();
-                    let ext = Path::new(target.as_str())
-                        .extension()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("");
-                    let to_type = if JS_EXTENSIONS.contains(&ext) {
-                        None
-                    } else {
-                        Some(NodeKind::Asset)
-                    };
-                    (target_rel, to_type)
-                } else {
-                    continue;
-                }
-            } else if let Some(target) = resolve_alias_import(ctx.aliases, &spec) {
-                let target_rel = target
-                    .as_str()
-                    .strip_prefix(root_str)
-                    .unwrap_or(target.as_str())
-                    .trim_start_matches('/')
-                    .to_string();
-                let ext = Path::new(target.as_str())
-                    .extension()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("");
-                let to_type = if JS_EXTENSIONS.contains(&ext) {
-                    None
-                } else {
-                    Some(NodeKind::Asset)
-                };
-                (target_rel, to_type)
-            } else if is_node_builtin(&spec) {
-                (spec.clone(), Some(NodeKind::Builtin))
-            } else {
-                (spec.clone(), Some(NodeKind::External))
-            };
-            edges.push(Edge {
-                from: rel.to_string(),
-                to: target_str,
-                kind: EdgeType::Regular,
-                from_type: None,
-                to_type,
-            });
-        }
-        Ok(edges)
+        let esm = esm_source(&src);
+        let info = parse_source(&esm, "jsx", FileName::Custom(path.as_str().into()))?;
+        Ok(module_edges(path, &src, info, ctx))
     }
 }
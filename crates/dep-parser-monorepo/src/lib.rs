@@ -1,8 +1,159 @@
+use glob::{MatchOptions, Pattern};
+use jsonc_parser::{ParseOptions, parse_to_serde_value};
+use serde::Deserialize;
 use vfs::VfsPath;
 
-use dep_core::{Context, Edge, Logger, Parser};
+use dep_core::{Context, Edge, EdgeType, LogLevel, Logger, NodeKind, Parser};
 use dep_parser_package::package_util::{Package, find_packages};
 
+/// Files that declare the member packages of a monorepo, in the order they
+/// are looked up at the root.
+pub const WORKSPACE_FILES: &[&str] = &[
+    "pnpm-workspace.yaml",
+    "pnpm-workspace.yml",
+    "package.json",
+    "lerna.json",
+    "rush.json",
+];
+
+#[derive(Deserialize)]
+struct PnpmWorkspace {
+    #[serde(default)]
+    packages: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NpmWorkspaces {
+    List(Vec<String>),
+    /// Yarn classic allows `{ "packages": [...], "nohoist": [...] }`
+    Object {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+#[derive(Deserialize)]
+struct RootPackage {
+    workspaces: Option<NpmWorkspaces>,
+}
+
+#[derive(Deserialize)]
+struct LernaConfig {
+    packages: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct RushConfig {
+    #[serde(default)]
+    projects: Vec<RushProject>,
+}
+
+#[derive(Deserialize)]
+struct RushProject {
+    #[serde(rename = "projectFolder")]
+    project_folder: String,
+}
+
+/// Member packages declared by one workspace file.
+#[derive(Clone, Debug)]
+pub struct WorkspaceConfig {
+    /// The file declaring the members
+    pub file: VfsPath,
+    /// Globs of member package folders, relative to the file's folder.
+    /// Patterns starting with `!` exclude folders.
+    pub patterns: Vec<String>,
+}
+
+impl WorkspaceConfig {
+    /// Whether the package in folder `dir` is a declared member. The
+    /// workspace root itself always is.
+    pub fn contains(&self, dir: &VfsPath) -> bool {
+        let base = self.file.parent();
+        let base = base.as_str().trim_end_matches('/');
+        let Some(rel) = dir.as_str().strip_prefix(base) else {
+            return false;
+        };
+        let rel = rel.trim_matches('/');
+        if rel.is_empty() {
+            return true;
+        }
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::default()
+        };
+        let matches = |pattern: &str| {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            Pattern::new(pattern).is_ok_and(|p| p.matches_with(rel, options))
+        };
+        let (excludes, includes): (Vec<&String>, Vec<&String>) =
+            self.patterns.iter().partition(|p| p.starts_with('!'));
+        includes.iter().any(|p| matches(p)) && !excludes.iter().any(|p| matches(&p[1..]))
+    }
+}
+
+/// Read the member patterns from a workspace file. Returns `None` when the
+/// file declares no workspace, e.g. a `package.json` without `workspaces`.
+pub fn parse_workspace_file(path: &VfsPath) -> anyhow::Result<Option<WorkspaceConfig>> {
+    let contents = path.read_to_string()?;
+    let patterns = match path.filename().as_str() {
+        "pnpm-workspace.yaml" | "pnpm-workspace.yml" => {
+            let workspace: Option<PnpmWorkspace> = serde_yaml::from_str(&contents)?;
+            workspace.map(|w| w.packages)
+        }
+        "package.json" => {
+            let Ok(root) = serde_json::from_str::<RootPackage>(&contents) else {
+                return Ok(None);
+            };
+            root.workspaces.map(|w| match w {
+                NpmWorkspaces::List(packages) | NpmWorkspaces::Object { packages } => packages,
+            })
+        }
+        "lerna.json" => {
+            let lerna: LernaConfig = parse_jsonc(&contents)?;
+            Some(lerna.packages.unwrap_or_else(|| vec!["packages/*".to_string()]))
+        }
+        "rush.json" => {
+            let rush: RushConfig = parse_jsonc(&contents)?;
+            Some(rush.projects.into_iter().map(|p| p.project_folder).collect())
+        }
+        _ => None,
+    };
+    Ok(patterns.map(|patterns| WorkspaceConfig {
+        file: path.clone(),
+        patterns,
+    }))
+}
+
+fn parse_jsonc<T: serde::de::DeserializeOwned>(contents: &str) -> anyhow::Result<T> {
+    let value = parse_to_serde_value(contents, &ParseOptions::default())?
+        .unwrap_or(serde_json::Value::Object(Default::default()));
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Load every workspace file at `root`. An empty list means the project is
+/// not a monorepo.
+pub fn load_workspaces(root: &VfsPath, logger: &dyn Logger) -> anyhow::Result<Vec<WorkspaceConfig>> {
+    let mut configs = Vec::new();
+    for name in WORKSPACE_FILES {
+        let path = root.join(name)?;
+        if !path.exists()? {
+            continue;
+        }
+        match parse_workspace_file(&path) {
+            Ok(Some(config)) => configs.push(config),
+            Ok(None) => {}
+            Err(e) => logger.log(
+                LogLevel::Error,
+                &format!("failed to parse {}: {e}", path.as_str()),
+            ),
+        }
+    }
+    Ok(configs)
+}
+
+/// Emits an edge from each workspace file to the member packages it
+/// declares.
 pub struct MonorepoParser;
 
 impl Parser for MonorepoParser {
@@ -10,34 +161,49 @@ impl Parser for MonorepoParser {
         "monorepo"
     }
     fn can_parse(&self, path: &VfsPath) -> bool {
-        let name = path.filename();
-        name == "pnpm-workspace.yml" || name == "package.json"
+        WORKSPACE_FILES.contains(&path.filename().as_str())
     }
 
-    fn parse(&self, path: &VfsPath, _ctx: &Context) -> anyhow::Result<Vec<Edge>> {
-        let _ = path.read_to_string();
-        Ok(Vec::new())
+    fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
+        let Some(config) = parse_workspace_file(path)? else {
+            return Ok(Vec::new());
+        };
+        let root_str = ctx.root.as_str().trim_end_matches('/');
+        let rel = path
+            .as_str()
+            .strip_prefix(root_str)
+            .unwrap_or(path.as_str())
+            .trim_start_matches('/');
+        let mut edges = Vec::new();
+        for package in ctx.packages {
+            let Some(name) = &package.name else {
+                continue;
+            };
+            if package.dir == path.parent() || !config.contains(&package.dir) {
+                continue;
+            }
+            edges.push(Edge {
+                from: rel.to_string(),
+                to: name.clone(),
+                kind: EdgeType::Regular,
+                from_type: None,
+                to_type: Some(NodeKind::Package),
+                ..Default::default()
+            });
+        }
+        Ok(edges)
     }
 }
 
-/// Load monorepo package information. Currently this simply finds all packages
-/// in the tree via `find_packages` but also parses workspace files to satisfy
-/// the API requirement.
+/// Load the packages of a monorepo: the members declared by its workspace
+/// files, or every package found when there are none.
 pub fn load_monorepo_packages(root: &VfsPath, logger: &dyn Logger) -> anyhow::Result<Vec<Package>> {
-    let _ = parse_workspace_files(root);
-    find_packages(root, logger)
-}
-
-fn parse_workspace_files(root: &VfsPath) -> anyhow::Result<()> {
-    if let Ok(path) = root.join("pnpm-workspace.yml")
-        && path.exists().unwrap_or(false) {
-            let _ = path.read_to_string();
-        }
-    if let Ok(path) = root.join("package.json")
-        && path.exists().unwrap_or(false) {
-            let _ = path.read_to_string();
-        }
-    Ok(())
+    let workspaces = load_workspaces(root, logger)?;
+    let mut packages = find_packages(root, logger)?;
+    if !workspaces.is_empty() {
+        packages.retain(|p| workspaces.iter().any(|w| w.contains(&p.dir)));
+    }
+    Ok(packages)
 }
 
 #[cfg(test)]
@@ -63,4 +229,56 @@ mod tests {
         assert!(names.contains(&"a"));
         assert!(names.contains(&"b"));
     }
+
+    fn member_names(files: &[(&str, &str)]) -> Vec<String> {
+        let fs = TestFS::new(files.iter().copied());
+        let root = fs.root();
+        let logger = dep_core::EmptyLogger;
+        let mut names: Vec<_> = load_monorepo_packages(&root, &logger)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    const PACKAGES: &[(&str, &str)] = &[
+        ("package.json", r#"{"name":"root"}"#),
+        ("packages/a/package.json", r#"{"name":"a"}"#),
+        ("packages/a/fixtures/x/package.json", r#"{"name":"fixture"}"#),
+        ("packages/legacy/package.json", r#"{"name":"legacy"}"#),
+        ("apps/web/package.json", r#"{"name":"web"}"#),
+    ];
+
+    #[test]
+    fn test_workspace_files_declare_members() {
+        let with = |file: (&'static str, &'static str)| {
+            let mut files: Vec<(&str, &str)> = PACKAGES.iter().copied().filter(|(p, _)| *p != file.0).collect();
+            files.push(file);
+            member_names(&files)
+        };
+        let pnpm = "packages:\n  - 'packages/*'\n  - '!packages/legacy'\n  - apps/*\n";
+        assert_eq!(with(("pnpm-workspace.yaml", pnpm)), ["a", "root", "web"]);
+        assert_eq!(with(("pnpm-workspace.yml", pnpm)), ["a", "root", "web"]);
+        assert_eq!(
+            with(("package.json", r#"{"name":"root","workspaces":["packages/*"]}"#)),
+            ["a", "legacy", "root"]
+        );
+        assert_eq!(
+            with(("package.json", r#"{"name":"root","workspaces":{"packages":["apps/**"]}}"#)),
+            ["root", "web"]
+        );
+        assert_eq!(with(("lerna.json", "{}")), ["a", "legacy", "root"]);
+        assert_eq!(
+            with((
+                "rush.json",
+                r#"{
+                    // comments are allowed in rush.json
+                    "projects": [{"packageName": "web", "projectFolder": "apps/web"}]
+                }"#,
+            )),
+            ["root", "web"]
+        );
+    }
 }
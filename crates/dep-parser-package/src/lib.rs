@@ -6,22 +6,48 @@ use std::path::Path;
 use vfs::VfsPath;
 
 use dep_core::{Context, Edge, Parser};
-use dep_core::{NodeKind, EdgeType};
+use dep_core::{Dependency, DependencyKind, NodeKind, EdgeType};
 
 #[derive(Deserialize)]
 struct RawPackage {
     name: Option<String>,
     main: Option<String>,
+    module: Option<String>,
+    types: Option<String>,
+    bin: Option<serde_json::Value>,
+    exports: Option<serde_json::Value>,
     dependencies: Option<HashMap<String, String>>,
     #[serde(rename = "devDependencies")]
     dev_dependencies: Option<HashMap<String, String>>,
+    #[serde(rename = "peerDependencies")]
+    peer_dependencies: Option<HashMap<String, String>>,
+    #[serde(rename = "optionalDependencies")]
+    optional_dependencies: Option<HashMap<String, String>>,
 }
 
-fn read_package(path: &VfsPath) -> anyhow::Result<Option<RawPackage>> {
+/// Read a package.json, skipping packages that are not workspace members
+/// when the project is a monorepo (see `Context::packages`).
+fn read_package(path: &VfsPath, ctx: &Context) -> anyhow::Result<Option<RawPackage>> {
+    if !ctx.packages.iter().any(|p| p.dir == path.parent()) {
+        return Ok(None);
+    }
     let content = path.read_to_string()?;
     Ok(serde_json::from_str(&content).ok())
 }
 
+/// Collect every string in a `bin` or `exports` value, whatever the nesting
+/// of conditions and subpaths.
+fn string_leaves<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| string_leaves(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| string_leaves(v, out)),
+        _ => {}
+    }
+}
+
+/// Links a package to its entry files: `main`, `module`, `types`, `bin` and
+/// the targets of `exports`.
 pub struct PackageMainParser;
 
 impl Parser for PackageMainParser {
@@ -36,35 +62,59 @@ impl Parser for PackageMainParser {
     }
 
     fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
-        let Some(raw) = read_package(path)? else {
+        let Some(raw) = read_package(path, ctx)? else {
             return Ok(Vec::new());
         };
         let Some(name) = raw.name else {
             return Ok(Vec::new());
         };
-        let mut edges = Vec::new();
-        if let Some(main) = raw.main
-            && let Ok(main_path) = path.parent().join(&main)
-                && main_path.exists().unwrap_or(false) {
-                    let root_str = ctx.root.as_str().trim_end_matches('/');
-                    let rel = main_path
-                        .as_str()
-                        .strip_prefix(root_str)
-                        .unwrap_or(main_path.as_str())
-                        .trim_start_matches('/')
-                        .to_string();
-                    edges.push(Edge {
-                        from: name.clone(),
-                        to: rel,
-                        kind: EdgeType::Regular,
-                        from_type: Some(NodeKind::Package),
-                        to_type: None,
-                    });
-                }
+        let root_str = ctx.root.as_str().trim_end_matches('/');
+        let mut targets: Vec<&str> = [&raw.main, &raw.module, &raw.types]
+            .into_iter()
+            .flatten()
+            .map(|s| s.as_str())
+            .collect();
+        for value in raw.bin.iter().chain(&raw.exports) {
+            string_leaves(value, &mut targets);
+        }
+        let mut edges: Vec<Edge> = Vec::new();
+        for target in targets {
+            // Subpath patterns like `./features/*.js` don't name a single file
+            if target.contains('*') {
+                continue;
+            }
+            let Ok(entry_path) = path.parent().join(target) else {
+                continue;
+            };
+            if !entry_path.exists().unwrap_or(false) {
+                continue;
+            }
+            let rel = entry_path
+                .as_str()
+                .strip_prefix(root_str)
+                .unwrap_or(entry_path.as_str())
+                .trim_start_matches('/')
+                .to_string();
+            if edges.iter().any(|e| e.to == rel) {
+                continue;
+            }
+            edges.push(Edge {
+                from: name.clone(),
+                to: rel,
+                kind: EdgeType::Regular,
+                from_type: Some(NodeKind::Package),
+                to_type: None,
+                ..Default::default()
+            });
+        }
         Ok(edges)
     }
 }
 
+/// Links a `package.json` to the package it declares, and the package to the
+/// packages it declares as dependencies. Each dependency edge carries the
+/// dependency kind and the declared version range; a package listed in
+/// several fields gets one edge per field.
 pub struct PackageDepsParser;
 
 impl Parser for PackageDepsParser {
@@ -78,37 +128,54 @@ impl Parser for PackageDepsParser {
             == Some("package.json")
     }
 
-    fn parse(&self, path: &VfsPath, _ctx: &Context) -> anyhow::Result<Vec<Edge>> {
-        let Some(raw) = read_package(path)? else {
+    fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
+        let Some(raw) = read_package(path, ctx)? else {
             return Ok(Vec::new());
         };
         let Some(name) = raw.name else {
             return Ok(Vec::new());
         };
-        let mut edges = Vec::new();
+        let root_str = ctx.root.as_str().trim_end_matches('/');
+        let rel = path
+            .as_str()
+            .strip_prefix(root_str)
+            .unwrap_or(path.as_str())
+            .trim_start_matches('/');
+        let mut edges = vec![Edge {
+            from: rel.to_string(),
+            to: name.clone(),
+            kind: EdgeType::Regular,
+            from_type: None,
+            to_type: Some(NodeKind::Package),
+            ..Default::default()
+        }];
 
-        let mut deps = HashMap::new();
-        if let Some(map) = raw.dependencies {
-            deps.extend(map);
-        }
-        if let Some(map) = raw.dev_dependencies {
-            deps.extend(map);
-        }
-
-        for (dep, ver) in deps {
-            let workspace = ver.starts_with("workspace:");
-            let to_type = if workspace {
-                Some(NodeKind::Package)
-            } else {
-                Some(NodeKind::External)
-            };
-            edges.push(Edge {
-                from: name.clone(),
-                to: dep.clone(),
-                kind: EdgeType::Regular,
-                from_type: Some(NodeKind::Package),
-                to_type,
-            });
+        let fields = [
+            (DependencyKind::Prod, raw.dependencies),
+            (DependencyKind::Dev, raw.dev_dependencies),
+            (DependencyKind::Peer, raw.peer_dependencies),
+            (DependencyKind::Optional, raw.optional_dependencies),
+        ];
+        for (kind, deps) in fields {
+            let mut deps: Vec<_> = deps.unwrap_or_default().into_iter().collect();
+            deps.sort();
+            for (dep, version) in deps {
+                let workspace = version.starts_with("workspace:");
+                let to_type = if workspace {
+                    Some(NodeKind::Package)
+                } else {
+                    Some(NodeKind::External)
+                };
+                edges.push(Edge {
+                    from: name.clone(),
+                    to: dep,
+                    kind: EdgeType::Regular,
+                    from_type: Some(NodeKind::Package),
+                    to_type,
+                    dependency: Some(Dependency { kind, version }),
+                    ..Default::default()
+                });
+            }
         }
         Ok(edges)
     }
@@ -116,7 +183,7 @@ impl Parser for PackageDepsParser {
 
 #[cfg(test)]
 mod tests {
-    use dep_core::test_util::TestFS;
+    use dep_core::test_util::{TestFS, test_context};
 
     #[test]
     fn test_package_util_parse_and_deps() {
@@ -139,6 +206,78 @@ mod tests {
         assert!(p0.deps.contains(&("bar".to_string(), false)));
     }
 
+    #[test]
+    fn test_package_entry_edges() {
+        use dep_core::package_json::PackageManifest;
+        use dep_core::{Context, Parser};
+
+        let fs = TestFS::new([
+            (
+                "pkg/package.json",
+                r#"{"name":"pkg","main":"./lib/main.js","module":"lib/main.js",
+                    "bin":{"pkg":"./bin/cli.js"},
+                    "exports":{".":{"import":"./lib/main.js","types":"./lib/main.d.ts"},"./*":"./lib/*.js"}}"#,
+            ),
+            ("pkg/lib/main.js", ""),
+            ("pkg/bin/cli.js", ""),
+        ]);
+        let root = fs.root();
+        let path = root.join("pkg/package.json").unwrap();
+        let packages = [PackageManifest::load(&path).unwrap().unwrap()];
+        let ctx = Context {
+            packages: &packages,
+            ..test_context(&root)
+        };
+        let edges = crate::PackageMainParser.parse(&path, &ctx).unwrap();
+        let targets: Vec<_> = edges.iter().map(|e| (e.from.as_str(), e.to.as_str())).collect();
+        assert_eq!(targets, [("pkg", "pkg/lib/main.js"), ("pkg", "pkg/bin/cli.js")]);
+    }
+
+    #[test]
+    fn test_dependency_kinds() {
+        use dep_core::package_json::PackageManifest;
+        use dep_core::{Context, DependencyKind, NodeKind, Parser};
+
+        let fs = TestFS::new([(
+            "package.json",
+            r#"{"name":"app",
+                "dependencies":{"react":"^18.2.0","ui":"workspace:*"},
+                "devDependencies":{"vitest":"~1.0.0","react":"18.2.0"},
+                "peerDependencies":{"react":">=17"},
+                "optionalDependencies":{"fsevents":"2.x"}}"#,
+        )]);
+        let root = fs.root();
+        let path = root.join("package.json").unwrap();
+        let packages = [PackageManifest::load(&path).unwrap().unwrap()];
+        let ctx = Context {
+            packages: &packages,
+            ..test_context(&root)
+        };
+        let edges = crate::PackageDepsParser.parse(&path, &ctx).unwrap();
+        assert_eq!((edges[0].from.as_str(), edges[0].to.as_str()), ("package.json", "app"));
+        let edges = &edges[1..];
+        let deps: Vec<_> = edges
+            .iter()
+            .map(|e| {
+                let dep = e.dependency.as_ref().unwrap();
+                (e.to.as_str(), dep.kind, dep.version.as_str())
+            })
+            .collect();
+        assert_eq!(
+            deps,
+            [
+                ("react", DependencyKind::Prod, "^18.2.0"),
+                ("ui", DependencyKind::Prod, "workspace:*"),
+                ("react", DependencyKind::Dev, "18.2.0"),
+                ("vitest", DependencyKind::Dev, "~1.0.0"),
+                ("react", DependencyKind::Peer, ">=17"),
+                ("fsevents", DependencyKind::Optional, "2.x"),
+            ]
+        );
+        assert_eq!(edges[1].to_type, Some(NodeKind::Package));
+        assert_eq!(edges[0].to_type, Some(NodeKind::External));
+    }
+
     #[test]
     fn test_malformed_package_json() {
         use crate::package_util::find_packages;
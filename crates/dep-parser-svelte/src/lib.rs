@@ -0,0 +1,99 @@
+use std::path::Path;
+use swc_common::FileName;
+use vfs::VfsPath;
+
+use dep_core::{Context, Edge, Parser};
+use dep_parser_js::{Block, BlockTag, ModuleInfo, blank_except, blocks, module_edges, parse_source};
+
+/// Whether a script is the module-level script (`<script context="module">`,
+/// or `<script module>` in Svelte 5) rather than the instance script.
+fn is_module(script: &Block) -> bool {
+    script.attr("context").is_some_and(|(v, _)| v == "module") || script.has_attr("module")
+}
+
+/// Parse the instance and module scripts of a Svelte component. `export let`
+/// in the instance script declares a prop, so only the module script's
+/// exports are kept.
+pub fn script_info(src: &str, path: &VfsPath) -> anyhow::Result<ModuleInfo> {
+    let mut info = ModuleInfo::default();
+    for script in blocks(src, BlockTag::Script, 0) {
+        // `<script src>` only appears in `<svelte:head>`, pointing to a URL
+        if script.has_attr("src") {
+            continue;
+        }
+        let ext = match script.attr("lang").map(|(lang, _)| lang.as_str()) {
+            Some("ts" | "typescript") => "ts",
+            _ => "js",
+        };
+        let code = blank_except(src, script.content.clone());
+        let script_info = parse_source(&code, ext, FileName::Custom(path.as_str().into()))?;
+        info.imports.extend(script_info.imports);
+        if is_module(&script) {
+            info.exports.extend(script_info.exports);
+        }
+    }
+    Ok(info)
+}
+
+/// Parses Svelte components: the imports of their `<script>` and
+/// `<script context="module">` blocks.
+pub struct SvelteParser;
+
+impl Parser for SvelteParser {
+    fn name(&self) -> &'static str {
+        "svelte"
+    }
+
+    fn can_parse(&self, path: &VfsPath) -> bool {
+        Path::new(path.as_str())
+            .extension()
+            .and_then(|s| s.to_str())
+            == Some("svelte")
+    }
+
+    fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
+        let src = path.read_to_string()?;
+        let info = script_info(&src, path)?;
+        Ok(module_edges(path, &src, info, ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::test_util::{TestFS, test_context};
+    use dep_core::NodeKind;
+
+    #[test]
+    fn test_svelte_edges() {
+        let component = r#"<script context="module" lang="ts">
+  import { fetchPosts } from './api';
+  export const prerender = true;
+</script>
+
+<script lang="ts">
+  import Header from './Header';
+  export let title: string;
+</script>
+
+<svelte:head>
+  <script src="https://cdn.example.com/analytics.js"></script>
+</svelte:head>
+
+<Header {title} />
+"#;
+        let fs = TestFS::new([("Page.svelte", component), ("api.ts", ""), ("Header.svelte", "")]);
+        let root = fs.root();
+        let ctx = test_context(&root);
+        let edges = SvelteParser.parse(&root.join("Page.svelte").unwrap(), &ctx).unwrap();
+        let edges: Vec<_> = edges.iter().map(|e| (e.to.as_str(), e.to_type)).collect();
+        assert_eq!(
+            edges,
+            [
+                ("Page.svelte#prerender", Some(NodeKind::Symbol)),
+                ("api.ts", None),
+                ("Header.svelte", None),
+            ]
+        );
+    }
+}
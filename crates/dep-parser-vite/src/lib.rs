@@ -3,9 +3,9 @@ use std::path::Path;
 use std::sync::OnceLock;
 use vfs::{VfsFileType, VfsPath};
 
-use dep_core::js_resolve::JS_EXTENSIONS;
-use dep_core::{Context, Edge, Parser};
-use dep_core::{EdgeType, NodeKind};
+use dep_core::js_resolve::{JS_EXTENSIONS, is_module_extension};
+use dep_core::{Context, Edge, Location, Parser};
+use dep_core::{Binding, EdgeType, ImportKind, NodeKind};
 
 fn expand_glob(base: &VfsPath, pat: &str) -> anyhow::Result<Vec<VfsPath>> {
     let pattern = match pat.strip_prefix("./") {
@@ -73,6 +73,11 @@ impl Parser for ViteParser {
         let mut edges = Vec::new();
         for cap in re.captures_iter(&src) {
             let pattern = cap[1].to_string();
+            let location = Location::from_offset(
+                rel,
+                &src,
+                cap.get(1).expect("group 1 always matches").start(),
+            );
             let Ok(files) = expand_glob(&dir, &pattern) else {
                 continue;
             };
@@ -86,7 +91,7 @@ impl Parser for ViteParser {
                     .extension()
                     .and_then(|s| s.to_str())
                     .unwrap_or("");
-                let to_type = if JS_EXTENSIONS.contains(&ext) {
+                let to_type = if is_module_extension(ext) {
                     None
                 } else {
                     Some(NodeKind::Asset)
@@ -97,6 +102,11 @@ impl Parser for ViteParser {
                     kind: EdgeType::Regular,
                     from_type: None,
                     to_type,
+                    import_kind: Some(ImportKind::Glob),
+                    location: Some(location.clone()),
+                    symbols: vec![Binding::namespace()],
+                    specifier: Some(pattern.clone()),
+                    ..Default::default()
                 });
             }
         }
@@ -0,0 +1,188 @@
+use std::path::Path;
+use swc_common::{BytePos, FileName, Span, SyntaxContext};
+use vfs::VfsPath;
+
+use dep_core::{Context, Edge, ImportKind, Parser};
+use dep_parser_css::{Reference, Syntax, collect_references, reference_edges};
+use dep_parser_js::{BlockTag, Import, ModuleInfo, blank_except, blocks, module_edges, parse_source};
+
+/// Parse the `<script>` and `<script setup>` blocks of a component with the
+/// JS parser, honouring `lang`. A `<script src>` becomes an import of that
+/// file.
+pub fn script_info(src: &str, path: &VfsPath) -> anyhow::Result<ModuleInfo> {
+    let mut info = ModuleInfo::default();
+    for block in blocks(src, BlockTag::Script, 0) {
+        if let Some((spec, offset)) = block.attr("src") {
+            info.imports.push(Import {
+                specifier: spec.clone(),
+                kind: ImportKind::Script,
+                span: Span::new(
+                    BytePos(*offset as u32),
+                    BytePos((offset + spec.len()) as u32),
+                    SyntaxContext::empty(),
+                ),
+                bindings: Vec::new(),
+            });
+            continue;
+        }
+        let ext = match block.attr("lang").map(|(lang, _)| lang.as_str()) {
+            Some("ts") => "ts",
+            Some("tsx") => "tsx",
+            Some("jsx") => "jsx",
+            _ => "js",
+        };
+        let code = blank_except(src, block.content.clone());
+        let block_info = parse_source(&code, ext, FileName::Custom(path.as_str().into()))?;
+        info.imports.extend(block_info.imports);
+        info.exports.extend(block_info.exports);
+    }
+    Ok(info)
+}
+
+/// Collect the stylesheets a component loads: `<style src>` and what its
+/// `<style>` blocks reference, in the syntax named by `lang`.
+pub fn style_references(src: &str) -> Vec<(Reference, Syntax)> {
+    let mut refs = Vec::new();
+    for block in blocks(src, BlockTag::Style, 0) {
+        let syntax = match block.attr("lang").map(|(lang, _)| lang.as_str()) {
+            None | Some("css" | "postcss") => Syntax::Css,
+            Some(lang) => match Syntax::from_extension(lang) {
+                Some(syntax) => syntax,
+                // Stylus and other languages are not followed
+                None => continue,
+            },
+        };
+        if let Some((spec, offset)) = block.attr("src") {
+            let reference = Reference {
+                specifier: spec.clone(),
+                kind: ImportKind::Style,
+                offset: *offset,
+            };
+            refs.push((reference, syntax));
+            continue;
+        }
+        let code = blank_except(src, block.content.clone());
+        refs.extend(collect_references(&code, syntax).into_iter().map(|r| (r, syntax)));
+    }
+    refs
+}
+
+/// Parses Vue single-file components: imports of the `<script>` and
+/// `<script setup>` blocks, and the stylesheets, fonts and images of the
+/// `<style>` blocks.
+pub struct VueParser;
+
+impl Parser for VueParser {
+    fn name(&self) -> &'static str {
+        "vue"
+    }
+
+    fn can_parse(&self, path: &VfsPath) -> bool {
+        Path::new(path.as_str())
+            .extension()
+            .and_then(|s| s.to_str())
+            == Some("vue")
+    }
+
+    fn parse(&self, path: &VfsPath, ctx: &Context) -> anyhow::Result<Vec<Edge>> {
+        let src = path.read_to_string()?;
+        let info = script_info(&src, path)?;
+        let mut edges = module_edges(path, &src, info, ctx);
+        for (reference, syntax) in style_references(&src) {
+            edges.extend(reference_edges(path, &src, vec![reference], syntax, ctx));
+        }
+        Ok(edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::test_util::{TestFS, test_context};
+    use dep_core::NodeKind;
+
+    const COMPONENT: &str = r#"<script lang="ts">
+import type { Props } from './types'
+export default { name: 'Card' }
+</script>
+
+<script setup lang="ts">
+import { ref } from 'vue'
+import Button from './Button.vue'
+const count = ref<number>(0)
+</script>
+
+<template>
+  <Button @click="count++">{{ count }}</Button>
+</template>
+
+<style scoped lang="scss">
+@use './theme';
+.card { background: url(./bg.png); }
+</style>
+<style src="./print.css"></style>
+"#;
+
+    #[test]
+    fn test_script_blocks() {
+        let fs = TestFS::new([("Card.vue", COMPONENT)]);
+        let info = script_info(COMPONENT, &fs.root().join("Card.vue").unwrap()).unwrap();
+        let imports: Vec<_> = info.imports.iter().map(|i| (i.specifier.as_str(), i.kind)).collect();
+        assert_eq!(
+            imports,
+            [("./types", ImportKind::Type), ("vue", ImportKind::Static), ("./Button.vue", ImportKind::Static)]
+        );
+        assert_eq!(info.exports.len(), 1);
+        // Spans point into the component source
+        let span = info.imports[2].span;
+        assert_eq!(&COMPONENT[span.lo.0 as usize..span.hi.0 as usize], "'./Button.vue'");
+    }
+
+    #[test]
+    fn test_style_blocks() {
+        let refs: Vec<_> = style_references(COMPONENT)
+            .into_iter()
+            .map(|(r, syntax)| (r.specifier, r.kind, syntax))
+            .collect();
+        assert_eq!(
+            refs,
+            [
+                ("./theme".to_string(), ImportKind::Style, Syntax::Scss),
+                ("./bg.png".to_string(), ImportKind::Url, Syntax::Scss),
+                ("./print.css".to_string(), ImportKind::Style, Syntax::Css),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vue_edges() {
+        let fs = TestFS::new([
+            ("Card.vue", COMPONENT),
+            ("types.ts", ""),
+            ("Button.vue", "<script setup>\nimport Icon from './Icon'\n</script>"),
+            ("Icon.vue", ""),
+            ("_theme.scss", ""),
+            ("bg.png", ""),
+            ("print.css", ""),
+        ]);
+        let root = fs.root();
+        let ctx = test_context(&root);
+        let edges = VueParser.parse(&root.join("Card.vue").unwrap(), &ctx).unwrap();
+        let edges: Vec<_> = edges.iter().map(|e| (e.to.as_str(), e.to_type)).collect();
+        assert_eq!(
+            edges,
+            [
+                ("Card.vue#default", Some(NodeKind::Symbol)),
+                ("types.ts", None),
+                ("vue", Some(NodeKind::External)),
+                ("Button.vue", None),
+                ("_theme.scss", Some(NodeKind::Asset)),
+                ("bg.png", Some(NodeKind::Asset)),
+                ("print.css", Some(NodeKind::Asset)),
+            ]
+        );
+        // `.vue` is tried for imports without an extension
+        let edges = VueParser.parse(&root.join("Button.vue").unwrap(), &ctx).unwrap();
+        assert_eq!(edges[0].to, "Icon.vue");
+    }
+}
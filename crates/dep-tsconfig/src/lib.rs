@@ -1,80 +1,330 @@
 use jsonc_parser::ParseOptions;
 use jsonc_parser::parse_to_serde_value;
 use serde::Deserialize;
-use std::collections::HashMap;
+use serde_json::Value;
 use vfs::VfsPath;
 
+use dep_core::tsconfig::{PathMapping, TsConfig};
 use dep_core::{LogLevel, Logger};
 
+/// Deepest `extends` chain followed, guarding against cycles.
+const MAX_EXTENDS_DEPTH: usize = 16;
+
 #[derive(Deserialize)]
 struct TsConfigFile {
+    extends: Option<Extends>,
     #[serde(rename = "compilerOptions")]
     compiler_options: Option<CompilerOptions>,
+    references: Option<Vec<Reference>>,
+}
+
+/// `extends` is one config, or since TypeScript 5.0 a list of configs
+/// applied in order.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Extends {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct Reference {
+    path: String,
 }
 
 #[derive(Deserialize)]
 struct CompilerOptions {
     #[serde(rename = "baseUrl")]
     base_url: Option<String>,
-    paths: Option<HashMap<String, Vec<String>>>,
+    /// Kept as a JSON map to keep the order of the patterns
+    paths: Option<serde_json::Map<String, Value>>,
+    #[serde(rename = "rootDirs")]
+    root_dirs: Option<Vec<String>>,
+    #[serde(rename = "moduleSuffixes")]
+    module_suffixes: Option<Vec<String>>,
 }
 
-pub fn load_tsconfig_aliases(
+/// Compiler options of a config merged with those it extends, with paths
+/// resolved against the config declaring them.
+#[derive(Default)]
+struct Options {
+    base_url: Option<VfsPath>,
+    /// The patterns and the folder of the config declaring them
+    paths: Option<(VfsPath, Vec<PathMapping>)>,
+    root_dirs: Option<Vec<VfsPath>>,
+    module_suffixes: Option<Vec<String>>,
+}
+
+fn read_tsconfig(path: &VfsPath, logger: &dyn Logger) -> Option<TsConfigFile> {
+    let contents = match path.read_to_string() {
+        Ok(c) => c,
+        Err(e) => {
+            logger.log(LogLevel::Error, &format!("failed to read {}: {e}", path.as_str()));
+            return None;
+        }
+    };
+    match parse_to_serde_value(&contents, &ParseOptions::default()) {
+        Ok(Some(value)) => match serde_json::from_value(value) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                logger.log(LogLevel::Error, &format!("failed to parse {}: {e}", path.as_str()));
+                None
+            }
+        },
+        Ok(None) => Some(TsConfigFile {
+            extends: None,
+            compiler_options: None,
+            references: None,
+        }),
+        Err(e) => {
+            logger.log(LogLevel::Error, &format!("failed to parse {}: {e}", path.as_str()));
+            None
+        }
+    }
+}
+
+fn is_file(path: &VfsPath) -> bool {
+    path.is_file().unwrap_or(false)
+}
+
+/// The config file a path names: the file itself, with `.json` added, or
+/// the `tsconfig.json` of a folder.
+fn config_file(path: VfsPath) -> Option<VfsPath> {
+    if is_file(&path) {
+        return Some(path);
+    }
+    let with_ext = path.parent().join(format!("{}.json", path.filename())).ok();
+    let in_dir = path.join("tsconfig.json").ok();
+    [with_ext, in_dir].into_iter().flatten().find(is_file)
+}
+
+/// Find the config named by `extends`: a path relative to the extending
+/// config, or a package (`@tsconfig/node20/tsconfig.json`) in the
+/// `node_modules` of its folder or any folder above.
+fn resolve_extends(dir: &VfsPath, spec: &str) -> Option<VfsPath> {
+    if spec.starts_with('.') || spec.starts_with('/') {
+        return config_file(dir.join(spec).ok()?);
+    }
+    let mut dir = dir.clone();
+    loop {
+        if let Some(found) = dir
+            .join("node_modules")
+            .and_then(|m| m.join(spec))
+            .ok()
+            .and_then(config_file)
+        {
+            return Some(found);
+        }
+        if dir.is_root() {
+            return None;
+        }
+        dir = dir.parent();
+    }
+}
+
+/// Load the compiler options of the config at `path`, after those of the
+/// configs it extends.
+fn load_options(path: &VfsPath, file: &TsConfigFile, logger: &dyn Logger, depth: usize) -> Options {
+    let dir = path.parent();
+    let mut options = Options::default();
+    let extends = match &file.extends {
+        Some(Extends::One(spec)) => vec![spec.as_str()],
+        Some(Extends::Many(specs)) => specs.iter().map(String::as_str).collect(),
+        None => Vec::new(),
+    };
+    for spec in extends {
+        if depth >= MAX_EXTENDS_DEPTH {
+            logger.log(
+                LogLevel::Error,
+                &format!("{}: extends chain too deep, ignoring {spec}", path.as_str()),
+            );
+            break;
+        }
+        let Some(base_path) = resolve_extends(&dir, spec) else {
+            logger.log(
+                LogLevel::Error,
+                &format!("{}: cannot find extended config {spec}", path.as_str()),
+            );
+            continue;
+        };
+        let Some(base_file) = read_tsconfig(&base_path, logger) else {
+            continue;
+        };
+        let base = load_options(&base_path, &base_file, logger, depth + 1);
+        options.base_url = base.base_url.or(options.base_url);
+        options.paths = base.paths.or(options.paths);
+        options.root_dirs = base.root_dirs.or(options.root_dirs);
+        options.module_suffixes = base.module_suffixes.or(options.module_suffixes);
+    }
+    let Some(opts) = &file.compiler_options else {
+        return options;
+    };
+    if let Some(base_url) = &opts.base_url
+        && let Ok(p) = dir.join(base_url)
+    {
+        options.base_url = Some(p);
+    }
+    if let Some(paths) = &opts.paths {
+        let mappings = paths
+            .iter()
+            .map(|(pattern, targets)| PathMapping {
+                pattern: pattern.clone(),
+                targets: targets
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|t| t.as_str().map(str::to_string))
+                    .collect(),
+            })
+            .collect();
+        options.paths = Some((dir.clone(), mappings));
+    }
+    if let Some(root_dirs) = &opts.root_dirs {
+        options.root_dirs = Some(root_dirs.iter().filter_map(|r| dir.join(r).ok()).collect());
+    }
+    if let Some(suffixes) = &opts.module_suffixes {
+        options.module_suffixes = Some(suffixes.clone());
+    }
+    options
+}
+
+fn load_tsconfig(path: &VfsPath, file: &TsConfigFile, logger: &dyn Logger) -> TsConfig {
+    let options = load_options(path, file, logger, 0);
+    let (paths_dir, paths) = options.paths.unzip();
+    TsConfig {
+        path: path.clone(),
+        dir: path.parent(),
+        paths_base: options.base_url.or(paths_dir).unwrap_or_else(|| path.parent()),
+        paths: paths.unwrap_or_default(),
+        root_dirs: options.root_dirs.unwrap_or_default(),
+        module_suffixes: options.module_suffixes.unwrap_or_default(),
+    }
+}
+
+/// Load every `tsconfig.json` among `files` (outside `node_modules`) and the
+/// one at `root`, with the configs they name in `references`. Each config
+/// is followed by its references, so a solution-style `tsconfig.json` that
+/// only references `tsconfig.app.json` applies that config to its folder.
+/// Configs that cannot be read or parsed are logged and skipped.
+pub fn load_tsconfigs(
     root: &VfsPath,
+    files: &[VfsPath],
     logger: &dyn Logger,
-) -> anyhow::Result<Vec<(String, VfsPath)>> {
+) -> anyhow::Result<Vec<TsConfig>> {
+    let mut paths: Vec<VfsPath> = files
+        .iter()
+        .filter(|p| p.filename() == "tsconfig.json" && !p.as_str().contains("/node_modules/"))
+        .cloned()
+        .collect();
     if let Ok(path) = root.join("tsconfig.json")
-        && path.exists()? {
-            let contents = match path.read_to_string() {
-                Ok(c) => c,
-                Err(e) => {
-                    logger.log(
-                        LogLevel::Error,
-                        &format!("failed to read {}: {e}", path.as_str()),
-                    );
-                    return Ok(Vec::new());
-                }
+        && path.exists()?
+        && !paths.contains(&path)
+    {
+        paths.push(path);
+    }
+    paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+    let mut tsconfigs: Vec<TsConfig> = Vec::new();
+    for path in paths {
+        if tsconfigs.iter().any(|t| t.path == path) {
+            continue;
+        }
+        let Some(file) = read_tsconfig(&path, logger) else {
+            continue;
+        };
+        tsconfigs.push(load_tsconfig(&path, &file, logger));
+        for reference in file.references.iter().flatten() {
+            let Some(ref_path) = path
+                .parent()
+                .join(&reference.path)
+                .ok()
+                .and_then(config_file)
+            else {
+                logger.log(
+                    LogLevel::Error,
+                    &format!("{}: cannot find referenced project {}", path.as_str(), reference.path),
+                );
+                continue;
             };
-            let tsconfig: TsConfigFile =
-                match parse_to_serde_value(&contents, &ParseOptions::default()) {
-                    Ok(Some(value)) => match serde_json::from_value(value) {
-                        Ok(v) => v,
-                        Err(e) => {
-                            logger.log(
-                                LogLevel::Error,
-                                &format!("failed to parse tsconfig.json: {e}"),
-                            );
-                            return Ok(Vec::new());
-                        }
-                    },
-                    Ok(None) => TsConfigFile {
-                        compiler_options: None,
-                    },
-                    Err(e) => {
-                        logger.log(
-                            LogLevel::Error,
-                            &format!("failed to parse tsconfig.json: {e}"),
-                        );
-                        return Ok(Vec::new());
-                    }
-                };
-            if let Some(opts) = tsconfig.compiler_options {
-                let base = opts.base_url.as_deref().unwrap_or(".");
-                let base_path = root.join(base)?;
-                let mut aliases = Vec::new();
-                if let Some(paths) = opts.paths {
-                    for (alias, targets) in paths {
-                        if let Some(first) = targets.into_iter().next() {
-                            let alias_prefix = alias.trim_end_matches("/*");
-                            let target_prefix = first.trim_end_matches("/*");
-                            if let Ok(p) = base_path.join(target_prefix) {
-                                aliases.push((alias_prefix.to_string(), p));
-                            }
-                        }
-                    }
-                }
-                return Ok(aliases);
+            if tsconfigs.iter().any(|t| t.path == ref_path) {
+                continue;
+            }
+            if let Some(ref_file) = read_tsconfig(&ref_path, logger) {
+                tsconfigs.push(load_tsconfig(&ref_path, &ref_file, logger));
             }
         }
-    Ok(Vec::new())
+    }
+    Ok(tsconfigs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dep_core::EmptyLogger;
+    use dep_core::test_util::TestFS;
+
+    fn load(fs: &TestFS) -> Vec<TsConfig> {
+        let root = fs.root();
+        let files: Vec<VfsPath> = root
+            .walk_dir()
+            .unwrap()
+            .flatten()
+            .filter(|p| p.is_file().unwrap())
+            .collect();
+        load_tsconfigs(&root, &files, &EmptyLogger).unwrap()
+    }
+
+    #[test]
+    fn test_extends_chain() {
+        let fs = TestFS::new([
+            (
+                "node_modules/@acme/tsconfig/base.json",
+                r#"{"compilerOptions":{"baseUrl":"./src","rootDirs":["src","generated"]}}"#,
+            ),
+            (
+                "configs/paths.json",
+                r#"{"extends":"@acme/tsconfig/base","compilerOptions":{"paths":{"@/*":["*","../lib/*"]}}}"#,
+            ),
+            ("app/tsconfig.json", r#"{"extends":"../configs/paths.json"}"#),
+        ]);
+        let tsconfigs = load(&fs);
+        assert_eq!(tsconfigs.len(), 1);
+        let tsconfig = &tsconfigs[0];
+        assert_eq!(tsconfig.dir.as_str(), "/app");
+        // baseUrl is relative to the config declaring it
+        assert_eq!(tsconfig.paths_base.as_str(), "/node_modules/@acme/tsconfig/src");
+        assert_eq!(tsconfig.paths[0].targets, ["*", "../lib/*"]);
+        let root_dirs: Vec<_> = tsconfig.root_dirs.iter().map(|d| d.as_str()).collect();
+        assert_eq!(root_dirs, ["/node_modules/@acme/tsconfig/src", "/node_modules/@acme/tsconfig/generated"]);
+    }
+
+    #[test]
+    fn test_paths_without_base_url() {
+        let fs = TestFS::new([
+            ("base/tsconfig.json", r#"{"compilerOptions":{"paths":{"~/*":["./src/*"]}}}"#),
+            ("tsconfig.json", r#"{"extends":["./base/tsconfig.json"],"compilerOptions":{"strict":true}}"#),
+        ]);
+        let tsconfigs = load(&fs);
+        assert_eq!(tsconfigs[0].paths_base.as_str(), "/base");
+    }
+
+    #[test]
+    fn test_references() {
+        let fs = TestFS::new([
+            ("tsconfig.json", r#"{"files":[],"references":[{"path":"./tsconfig.app.json"},{"path":"./tsconfig.node.json"}]}"#),
+            ("tsconfig.app.json", r#"{"compilerOptions":{"paths":{"@/*":["./src/*"]}}}"#),
+            ("tsconfig.node.json", "{}"),
+        ]);
+        let paths: Vec<_> = load(&fs).iter().map(|t| t.path.as_str().to_string()).collect();
+        assert_eq!(paths, ["/tsconfig.json", "/tsconfig.app.json", "/tsconfig.node.json"]);
+    }
+
+    #[test]
+    fn test_extends_cycle() {
+        let fs = TestFS::new([
+            ("tsconfig.json", r#"{"extends":"./other.json"}"#),
+            ("other.json", r#"{"extends":"./tsconfig.json"}"#),
+        ]);
+        assert_eq!(load(&fs).len(), 1);
+    }
 }
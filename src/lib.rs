@@ -1,40 +1,111 @@
 use petgraph::graph::DiGraph;
 use std::sync::{Arc, Mutex};
+use vfs::VfsPath;
 
 pub use dep_core::*;
 pub use dep_traversal::{Walk, WalkBuilder};
-pub use dep_analysis::{filter_graph, prune_unconnected};
+pub use dep_analysis::{Cycle, FilterOptions, ImportFilter, filter_graph, find_cycles, import_graph, prune_unconnected};
+pub use dep_analysis::{BoundaryViolation, boundary_violations};
+pub use dep_analysis::{Dependent, Hop, dependents, import_paths};
+pub use dep_analysis::{IMPLICIT_ENTRYPOINTS, dead_files, find_entrypoints};
+pub use dep_analysis::{DependencyReport, MissingDependency, UnusedDependency, dependency_report};
+pub use dep_analysis::{DuplicatePackage, InstalledVersion, duplicate_packages};
+pub use dep_analysis::{Rule, Violation, check_rules};
+pub use dep_analysis::{SymbolUse, UnusedExport, symbol_graph, symbol_users, symbol_uses, unused_exports};
+pub use dep_analysis::{UnresolvedImport, unresolved_imports};
 pub use dep_output::{graph_to_dot, graph_to_json};
 
 pub mod output {
     pub use dep_output::*;
 }
 
-use dep_tsconfig::load_tsconfig_aliases;
+use dep_core::import_map::ImportMap;
+use dep_core::js_resolve::ResolveOptions;
+use dep_core::package_json::PackageManifest;
+use dep_tsconfig::load_tsconfigs;
+
+/// Import maps declared by the HTML pages, `importmap.json` and `deno.json`
+/// files among `files`.
+fn load_import_maps(files: &[VfsPath], logger: &dyn Logger) -> Vec<ImportMap> {
+    let mut import_maps = Vec::new();
+    for path in files {
+        let loaded = match path.filename().as_str() {
+            "importmap.json" | "deno.json" | "deno.jsonc" => ImportMap::load(path).map(|m| vec![m]),
+            name if name.ends_with(".html") => dep_parser_html::read_import_maps(path),
+            _ => continue,
+        };
+        match loaded {
+            Ok(maps) => import_maps.extend(maps.into_iter().filter(|m| !m.is_empty())),
+            Err(e) => logger.log(
+                LogLevel::Error,
+                &format!("failed to read import map from {}: {}", path.as_str(), e),
+            ),
+        }
+    }
+    import_maps
+}
 
 /// Build a dependency graph of all JS/TS files within `root`.
 pub fn build_dependency_graph(
     walk: &Walk,
     workers: Option<usize>,
     logger: &dyn Logger,
-) -> anyhow::Result<DiGraph<Node, EdgeType>> {
+) -> anyhow::Result<DiGraph<Node, EdgeData>> {
+    build_dependency_graph_with(walk, workers, &ResolveOptions::default(), logger)
+}
+
+/// Build a dependency graph like [`build_dependency_graph`], resolving
+/// imports with the given options.
+pub fn build_dependency_graph_with(
+    walk: &Walk,
+    workers: Option<usize>,
+    options: &ResolveOptions,
+    logger: &dyn Logger,
+) -> anyhow::Result<DiGraph<Node, EdgeData>> {
     let files = walk.collect_files(logger)?;
     logger.log(LogLevel::Debug, &format!("found {} files", files.len()));
     let root = walk.root();
-    let aliases = load_tsconfig_aliases(root, logger)?;
+    let tsconfigs = load_tsconfigs(root, &files, logger)?;
+    let import_maps = load_import_maps(&files, logger);
+    let mut packages = Vec::new();
+    for path in files.iter().filter(|p| p.filename() == "package.json") {
+        match PackageManifest::load(path) {
+            Ok(Some(manifest)) => packages.push(manifest),
+            Ok(None) => {}
+            Err(e) => logger.log(
+                LogLevel::Error,
+                &format!("failed to read {}: {}", path.as_str(), e),
+            ),
+        }
+    }
+    // In a monorepo, only the declared workspace members are packages
+    let workspaces = dep_parser_monorepo::load_workspaces(root, logger)?;
+    if !workspaces.is_empty() {
+        packages.retain(|p| workspaces.iter().any(|w| w.contains(&p.dir)));
+    }
+    packages.sort_by(|a, b| a.dir.as_str().cmp(b.dir.as_str()));
     let ctx = Context {
         root,
-        aliases: &aliases,
+        tsconfigs: &tsconfigs,
+        packages: &packages,
+        conditions: &options.conditions,
+        import_maps: &import_maps,
         logger,
     };
     let parsers: Vec<Box<dyn Parser>> = vec![
         Box::new(dep_parser_package::PackageMainParser),
         Box::new(dep_parser_package::PackageDepsParser),
+        Box::new(dep_parser_monorepo::MonorepoParser),
+        Box::new(dep_parser_lockfile::LockfileParser),
         Box::new(dep_parser_index::IndexParser),
         Box::new(dep_parser_js::JsParser),
         Box::new(dep_parser_vite::ViteParser),
         Box::new(dep_parser_mdx::MdxParser),
         Box::new(dep_parser_html::HtmlParser),
+        Box::new(dep_parser_css::CssParser),
+        Box::new(dep_parser_vue::VueParser),
+        Box::new(dep_parser_svelte::SvelteParser),
+        Box::new(dep_parser_astro::AstroParser),
     ];
     let workers = workers.unwrap_or_else(num_cpus::get);
     logger.log(
@@ -54,7 +125,11 @@ pub fn build_dependency_graph(
             let path_clone = path.clone();
             let should_parse = parsers.iter().any(|p| p.can_parse(&path_clone));
             if should_parse {
-                parsed_files.push(path_clone.clone());
+                let kind = parsers
+                    .iter()
+                    .filter(|p| p.can_parse(&path_clone))
+                    .find_map(|p| p.file_kind(&path_clone));
+                parsed_files.push((path_clone.clone(), kind));
             }
             s.spawn(move |_| {
                 for p in parsers {
@@ -99,7 +174,7 @@ pub fn build_dependency_graph(
     let root_str = root.as_str().trim_end_matches('/');
 
     // Create nodes for all parsed files
-    for path in &parsed_files {
+    for (path, kind) in &parsed_files {
         let rel = path
             .as_str()
             .strip_prefix(root_str)
@@ -108,7 +183,10 @@ pub fn build_dependency_graph(
         let parent_idx = ensure_folders(rel, &mut data, root_idx);
         let idx = ensure_node(rel, &mut data);
         if data.graph.find_edge(parent_idx, idx).is_none() {
-            data.graph.add_edge(parent_idx, idx, EdgeType::Regular);
+            data.graph.add_edge(parent_idx, idx, EdgeType::Regular.into());
+        }
+        if let Some(kind) = *kind {
+            attach_type(idx, kind, &mut data);
         }
     }
 
@@ -122,11 +200,21 @@ pub fn build_dependency_graph(
             attach_type(from_idx, kind, &mut data);
         }
 
-        let to_idx = if e.to.contains('/') || e.to.contains('.') {
+        let to_idx = if e.kind == EdgeType::Export {
+            // Symbol nodes are not files and don't belong in the folder tree
+            ensure_node(&e.to, &mut data)
+        } else if matches!(
+            e.to_type,
+            Some(NodeKind::External | NodeKind::Builtin | NodeKind::Package | NodeKind::Unresolved)
+        ) {
+            // Package names like `@scope/pkg` or `lodash@4.17.21` are not
+            // paths, and unresolved imports point to no file
+            ensure_node(&e.to, &mut data)
+        } else if e.to.contains('/') || e.to.contains('.') {
             let parent_idx = ensure_folders(&e.to, &mut data, root_idx);
             let idx = ensure_node(&e.to, &mut data);
             if data.graph.find_edge(parent_idx, idx).is_none() {
-                data.graph.add_edge(parent_idx, idx, EdgeType::Regular);
+                data.graph.add_edge(parent_idx, idx, EdgeType::Regular.into());
             }
             idx
         } else {
@@ -137,7 +225,18 @@ pub fn build_dependency_graph(
             attach_type(to_idx, kind, &mut data);
         }
 
-        data.graph.add_edge(from_idx, to_idx, e.kind.clone());
+        data.graph.add_edge(
+            from_idx,
+            to_idx,
+            EdgeData {
+                kind: e.kind.clone(),
+                import_kind: e.import_kind,
+                location: e.location.clone(),
+                symbols: e.symbols.clone(),
+                dependency: e.dependency.clone(),
+                specifier: e.specifier.clone(),
+            },
+        );
     }
 
     let res = data.graph;
@@ -181,7 +280,7 @@ mod tests {
     proptest! {
         #[test]
         fn prop_end_to_end(ext_a in proptest::sample::select(JS_EXTENSIONS), ext_b in proptest::sample::select(JS_EXTENSIONS)) {
-            let entries = vec![
+            let entries = [
                 ("proj/.gitignore".to_string(), b"ignored/".to_vec()),
                 (format!("proj/src/main.{ext_a}"), format!("import '../lib/util.{ext_b}';").into_bytes()),
                 (format!("proj/lib/util.{ext_b}"), Vec::new()),
@@ -31,6 +31,14 @@ impl std::fmt::Display for OutputFormat {
     }
 }
 
+/// Output of the reports run by subcommands
+#[derive(Clone, Copy, Default, clap::ValueEnum)]
+enum ReportFormat {
+    #[default]
+    Text,
+    Json,
+}
+
 #[derive(Deserialize)]
 struct FileConfig {
     include_external: Option<bool>,
@@ -38,8 +46,17 @@ struct FileConfig {
     include_folders: Option<bool>,
     include_assets: Option<bool>,
     include_packages: Option<bool>,
+    include_symbols: Option<bool>,
+    symbol_graph: Option<bool>,
     ignore_nodes: Option<Vec<String>>,
     ignore_paths: Option<Vec<String>>,
+    exclude_type_imports: Option<bool>,
+    only_type_imports: Option<bool>,
+    entrypoints: Option<Vec<String>>,
+    rules: Option<Vec<dep::Rule>>,
+    conditions: Option<Vec<String>>,
+    dependency_kinds: Option<Vec<dep::DependencyKind>>,
+    ignore_dependencies: Option<Vec<String>>,
     output: Option<PathBuf>,
     format: Option<OutputFormat>,
     workers: Option<usize>,
@@ -62,6 +79,9 @@ struct Args {
     #[arg(default_value = ".")]
     path: PathBuf,
 
+    #[command(subcommand)]
+    mode: Option<Mode>,
+
     /// Include external packages in output
     #[arg(long, default_value_t = true)]
     include_external: bool,
@@ -82,6 +102,15 @@ struct Args {
     #[arg(long, default_value_t = true)]
     include_packages: bool,
 
+    /// Include exported symbol nodes in output
+    #[arg(long, default_value_t = false)]
+    include_symbols: bool,
+
+    /// Output the symbol-level graph: modules linked to the symbols they
+    /// export and import
+    #[arg(long, default_value_t = false)]
+    symbol_graph: bool,
+
     /// Node names to ignore from output
     #[arg(long = "ignore-node")]
     ignore_nodes: Vec<String>,
@@ -90,6 +119,39 @@ struct Args {
     #[arg(long = "ignore", name = "PATTERN")]
     ignore_paths: Vec<String>,
 
+    /// Drop type-only imports, leaving the runtime dependency graph
+    #[arg(long, default_value_t = false, global = true, conflicts_with = "only_type_imports")]
+    exclude_type_imports: bool,
+
+    /// Keep only type-only imports
+    #[arg(long, default_value_t = false, global = true)]
+    only_type_imports: bool,
+
+    /// Entrypoint files or glob patterns, used by unused-exports and
+    /// dead-files
+    #[arg(long = "entry", global = true)]
+    entrypoints: Vec<String>,
+
+    /// Conditions to match in package.json `exports` and `imports`
+    /// [default: import, require, node]
+    #[arg(long = "condition", global = true)]
+    conditions: Vec<String>,
+
+    /// Only keep package dependency edges of these kinds: prod, dev, peer
+    /// or optional [default: all]. The dependencies report only filters
+    /// unused dependencies with it, as missing ones are declared nowhere
+    #[arg(long = "dependency-kind", value_name = "KIND", global = true)]
+    dependency_kinds: Vec<dep::DependencyKind>,
+
+    /// Package names or glob patterns the dependencies report never
+    /// reports, such as tools only run from package.json scripts
+    #[arg(long = "ignore-dependency", value_name = "PACKAGE", global = true)]
+    ignore_dependencies: Vec<String>,
+
+    /// Dependency rules, only read from the config file
+    #[arg(skip)]
+    rules: Vec<dep::Rule>,
+
     /// Output file path
     #[arg(long, default_value = "out.dot")]
     output: PathBuf,
@@ -119,6 +181,98 @@ struct Args {
     sfdp: bool,
 }
 
+/// Reports run on the dependency graph instead of writing it out
+#[derive(Clone, clap::Subcommand)]
+enum Mode {
+    /// List exported symbols that no other file imports
+    UnusedExports,
+    /// Report import cycles and fail when new ones are found
+    Cycles {
+        /// JSON file of known cycles; only cycles missing from it fail the run
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Write the current cycles to the baseline file instead of checking
+        #[arg(long, default_value_t = false, requires = "baseline")]
+        update_baseline: bool,
+    },
+    /// List the files that import a file, directly or transitively
+    Dependents {
+        /// File to query, relative to the project path
+        file: String,
+
+        /// Only follow this many imports back from the file
+        #[arg(long)]
+        depth: Option<usize>,
+    },
+    /// Show the shortest import chains from a file to a module or package
+    Why {
+        /// Starting file or glob pattern, relative to the project path
+        from: String,
+
+        /// File, folder, package or builtin to explain
+        to: String,
+
+        /// Print the chains as text or json
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+    },
+    /// List files and assets that no entrypoint reaches
+    DeadFiles {
+        /// Only start from --entry / `entrypoints`, without inferring
+        /// entrypoints from packages, HTML pages, tests and tool configs
+        #[arg(long, default_value_t = false)]
+        no_infer: bool,
+    },
+    /// Check the dependency rules from dep.toml and fail on violations
+    Check {
+        /// Print violations as text or json
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+    },
+    /// List imports that reach into another workspace package's files by
+    /// relative path or alias instead of through its package name
+    Boundaries {
+        /// Print violations as text or json
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+
+        /// Exit with a non-zero code when violations are found
+        #[arg(long, default_value_t = false)]
+        fail: bool,
+    },
+    /// List third-party packages installed in several versions, from the
+    /// lockfiles
+    Duplicates {
+        /// Print the report as text or json
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+    },
+    /// List imports of undeclared packages and declared dependencies that
+    /// are never imported, per package.json
+    Dependencies {
+        /// Print the report as text or json
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+
+        /// Exit with a non-zero code when missing or unused dependencies
+        /// are found
+        #[arg(long, default_value_t = false)]
+        fail: bool,
+    },
+    /// List imports that do not resolve: relative paths with no file behind
+    /// them and `#imports` missing from package.json
+    Unresolved {
+        /// Print unresolved imports as text or json
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+
+        /// Exit with a non-zero code when unresolved imports are found
+        #[arg(long, default_value_t = false)]
+        fail: bool,
+    },
+}
+
 fn default_color() -> bool {
     std::env::var("CI").map(|v| v.is_empty()).unwrap_or(true)
 }
@@ -149,8 +303,16 @@ fn main() -> anyhow::Result<()> {
         merge_arg!(include_folders);
         merge_arg!(include_assets);
         merge_arg!(include_packages);
+        merge_arg!(include_symbols);
+        merge_arg!(symbol_graph);
         merge_arg!(ignore_nodes);
         merge_arg!(ignore_paths);
+        merge_arg!(exclude_type_imports);
+        merge_arg!(only_type_imports);
+        merge_arg!(entrypoints);
+        merge_arg!(conditions);
+        merge_arg!(dependency_kinds);
+        merge_arg!(ignore_dependencies);
         merge_arg!(output);
         merge_arg!(format);
 
@@ -164,6 +326,8 @@ fn main() -> anyhow::Result<()> {
         merge_arg!(color);
         merge_arg!(prune);
         merge_arg!(sfdp);
+
+        args.rules = config.rules.unwrap_or_default();
     }
 
     let root: VfsPath = PhysicalFS::new(&args.path).into();
@@ -174,7 +338,24 @@ fn main() -> anyhow::Result<()> {
     let walk = dep::WalkBuilder::new(&root)
         .ignore_patterns(&args.ignore_paths)
         .build();
-    let mut graph = dep::build_dependency_graph(&walk, args.workers, &logger)?;
+    let mut options = dep::js_resolve::ResolveOptions::default();
+    if !args.conditions.is_empty() {
+        options.conditions = args.conditions.clone();
+    }
+    let mut graph = dep::build_dependency_graph_with(&walk, args.workers, &options, &logger)?;
+    if args.exclude_type_imports && args.only_type_imports {
+        anyhow::bail!("exclude_type_imports and only_type_imports cannot both be set");
+    }
+    let imports = if args.exclude_type_imports {
+        dep::ImportFilter::Runtime
+    } else if args.only_type_imports {
+        dep::ImportFilter::Types
+    } else {
+        dep::ImportFilter::All
+    };
+    if let Some(mode) = args.mode.clone() {
+        return run_mode(mode, &graph, &args, imports);
+    }
     if args.prune {
         let before = graph.node_count();
         dep::prune_unconnected(&mut graph);
@@ -183,14 +364,23 @@ fn main() -> anyhow::Result<()> {
             &format!("pruned {} nodes", before - graph.node_count()),
         );
     }
+    if args.symbol_graph {
+        graph = dep::symbol_graph(&graph);
+        args.include_symbols = true;
+    }
     let filtered = dep::filter_graph(
         &graph,
-        args.include_external,
-        args.include_builtins,
-        args.include_folders,
-        args.include_assets,
-        args.include_packages,
-        &args.ignore_nodes,
+        &dep::FilterOptions {
+            include_external: args.include_external,
+            include_builtin: args.include_builtins,
+            include_folders: args.include_folders,
+            include_assets: args.include_assets,
+            include_packages: args.include_packages,
+            include_symbols: args.include_symbols,
+            ignore_nodes: args.ignore_nodes.clone(),
+            imports,
+            dependency_kinds: args.dependency_kinds.clone(),
+        },
     );
     use dep_core::{NodeKind, EdgeType};
     use dep_core::{is_type_node, resolve_node_kind};
@@ -206,7 +396,7 @@ fn main() -> anyhow::Result<()> {
         counts.entry(kind).or_default().0 += 1;
     }
     for e in filtered.edge_references() {
-        if *e.weight() == EdgeType::TypeOf {
+        if e.weight().kind == EdgeType::TypeOf {
             continue;
         }
         if is_type_node(&filtered[e.source()]) || is_type_node(&filtered[e.target()]) {
@@ -248,9 +438,209 @@ fn main() -> anyhow::Result<()> {
         NodeKind::Folder,
         NodeKind::Asset,
         NodeKind::Package,
+        NodeKind::Symbol,
+        NodeKind::Unresolved,
     ] {
         let (nodes, edges) = counts.get(kind).cloned().unwrap_or((0, 0));
         println!("{}: {} nodes & {} edges", kind, nodes, edges);
     }
     Ok(())
 }
+
+fn run_mode(
+    mode: Mode,
+    graph: &petgraph::graph::DiGraph<dep_core::Node, dep_core::EdgeData>,
+    args: &Args,
+    imports: dep::ImportFilter,
+) -> anyhow::Result<()> {
+    match mode {
+        Mode::UnusedExports => {
+            let unused = dep::unused_exports(graph, &args.entrypoints);
+            for export in &unused {
+                match &export.location {
+                    Some(loc) => println!("{loc}: {}", export.name),
+                    None => println!("{}: {}", export.file, export.name),
+                }
+            }
+            println!("{} unused exports", unused.len());
+        }
+        Mode::Cycles {
+            baseline,
+            update_baseline,
+        } => {
+            let cycles = dep::find_cycles(graph, imports);
+            if let (Some(path), true) = (&baseline, update_baseline) {
+                let files: Vec<&Vec<String>> = cycles.iter().map(|c| &c.files).collect();
+                std::fs::write(path, serde_json::to_string_pretty(&files)?)?;
+                println!("Saved {} cycles to {}", cycles.len(), path.display());
+                return Ok(());
+            }
+            let known: Vec<Vec<String>> = match &baseline {
+                Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+                None => Vec::new(),
+            };
+            let mut new = 0;
+            for cycle in &cycles {
+                let is_new = !cycle.is_known(&known);
+                if is_new {
+                    new += 1;
+                }
+                let extra = cycle.files.len() + 1 - cycle.path.len();
+                let mut line = cycle.to_string();
+                if extra > 0 {
+                    line.push_str(&format!(" (+{extra} more files in this cycle)"));
+                }
+                if is_new && baseline.is_some() {
+                    line.push_str(" [new]");
+                }
+                println!("{line}");
+            }
+            println!("{} import cycles, {} new", cycles.len(), new);
+            if new > 0 {
+                anyhow::bail!("found {new} new import cycles");
+            }
+        }
+        Mode::Dependents { file, depth } => {
+            let file = file.trim_start_matches("./");
+            let Some(found) = dep::dependents(graph, file, depth, imports) else {
+                anyhow::bail!("{file} is not in the dependency graph");
+            };
+            for dependent in &found {
+                println!("{} [{}]", dependent.file, dependent.depth);
+                for hop in &dependent.chain {
+                    println!("    {hop}");
+                }
+            }
+            println!("{} files depend on {file}", found.len());
+        }
+        Mode::Why { from, to, format } => {
+            let from = from.trim_start_matches("./").to_string();
+            let to = to.trim_start_matches("./");
+            let paths = dep::import_paths(graph, std::slice::from_ref(&from), to, imports);
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&paths)?),
+                ReportFormat::Text => {
+                    for path in &paths {
+                        let mut files = vec![path[0].from.as_str()];
+                        files.extend(path.iter().map(|hop| hop.to.as_str()));
+                        println!("{}", files.join(" -> "));
+                        for hop in path {
+                            println!("    {hop}");
+                        }
+                    }
+                }
+            }
+            if paths.is_empty() {
+                anyhow::bail!("no import path from {from} to {to}");
+            }
+        }
+        Mode::DeadFiles { no_infer } => {
+            let entries = dep::find_entrypoints(graph, &args.entrypoints, !no_infer);
+            if entries.is_empty() {
+                anyhow::bail!("no entrypoints found, set them with --entry or `entrypoints`");
+            }
+            let dead = dep::dead_files(graph, &entries, imports);
+            for idx in &dead {
+                println!("{}", graph[*idx].name);
+            }
+            println!("{} unreachable files from {} entrypoints", dead.len(), entries.len());
+        }
+        Mode::Check { format } => {
+            if args.rules.is_empty() {
+                println!("No rules configured in dep.toml");
+                return Ok(());
+            }
+            for (i, rule) in args.rules.iter().enumerate() {
+                if rule.deny.is_empty() && rule.allow.is_empty() {
+                    anyhow::bail!("rule #{} needs `deny` or `allow` patterns", i + 1);
+                }
+            }
+            let violations = dep::check_rules(graph, &args.rules, imports);
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&violations)?),
+                ReportFormat::Text => {
+                    for violation in &violations {
+                        println!("{violation}");
+                    }
+                    println!("{} rules, {} violations", args.rules.len(), violations.len());
+                }
+            }
+            if !violations.is_empty() {
+                anyhow::bail!("found {} rule violations", violations.len());
+            }
+        }
+        Mode::Boundaries { format, fail } => {
+            let violations = dep::boundary_violations(graph, imports);
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&violations)?),
+                ReportFormat::Text => {
+                    for violation in &violations {
+                        println!("{violation}");
+                    }
+                    println!("{} package boundary violations", violations.len());
+                }
+            }
+            if fail && !violations.is_empty() {
+                anyhow::bail!("found {} package boundary violations", violations.len());
+            }
+        }
+        Mode::Duplicates { format } => {
+            let duplicates = dep::duplicate_packages(graph);
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&duplicates)?),
+                ReportFormat::Text => {
+                    for package in &duplicates {
+                        println!("{}", package.name);
+                        for version in &package.versions {
+                            println!("  {} <- {}", version.version, version.dependents.join(", "));
+                        }
+                    }
+                    println!("{} packages installed in several versions", duplicates.len());
+                }
+            }
+        }
+        Mode::Dependencies { format, fail } => {
+            let mut report = dep::dependency_report(graph, imports);
+            report.filter(&args.dependency_kinds, &args.ignore_dependencies);
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                ReportFormat::Text => {
+                    for missing in &report.missing {
+                        println!("missing {missing}");
+                    }
+                    for unused in &report.unused {
+                        println!("unused  {unused}");
+                    }
+                    println!(
+                        "{} missing, {} unused dependencies",
+                        report.missing.len(),
+                        report.unused.len()
+                    );
+                }
+            }
+            if fail && (!report.missing.is_empty() || !report.unused.is_empty()) {
+                anyhow::bail!(
+                    "found {} missing and {} unused dependencies",
+                    report.missing.len(),
+                    report.unused.len()
+                );
+            }
+        }
+        Mode::Unresolved { format, fail } => {
+            let unresolved = dep::unresolved_imports(graph, imports);
+            match format {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&unresolved)?),
+                ReportFormat::Text => {
+                    for import in &unresolved {
+                        println!("{import}");
+                    }
+                    println!("{} unresolved imports", unresolved.len());
+                }
+            }
+            if fail && !unresolved.is_empty() {
+                anyhow::bail!("found {} unresolved imports", unresolved.len());
+            }
+        }
+    }
+    Ok(())
+}
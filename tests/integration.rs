@@ -1,11 +1,24 @@
 use dep::{
-    build_dependency_graph, filter_graph, graph_to_dot, graph_to_json,
-    EmptyLogger, WalkBuilder, NodeKind,
+    boundary_violations, build_dependency_graph, build_dependency_graph_with, check_rules, dead_files, dependency_report, duplicate_packages, dependents, filter_graph, find_cycles, find_entrypoints, import_paths, graph_to_dot, graph_to_json, symbol_graph, symbol_users, unresolved_imports,
+    unused_exports, EmptyLogger, FilterOptions, Rule, ImportFilter, ImportKind, WalkBuilder, NodeKind,
 };
 use dep_core::test_util::TestFS;
 use dep_core::{resolve_node_kind, js_resolve::JS_EXTENSIONS};
+use petgraph::graph::DiGraph;
+use petgraph::visit::EdgeRef;
 use proptest::prelude::*;
 
+/// Filter options keeping every node kind but folders and symbols.
+fn everything() -> FilterOptions {
+    FilterOptions {
+        include_external: true,
+        include_builtin: true,
+        include_assets: true,
+        include_packages: true,
+        ..Default::default()
+    }
+}
+
 #[test]
 fn test_js_parser_basic() {
     let fs = TestFS::new([("a.js", "import './b.js';"), ("b.js", "")]);
@@ -259,11 +272,11 @@ fn test_folder_nodes() {
     assert!(graph.find_edge(folder_idx, file_idx).is_some());
     assert_eq!(resolve_node_kind(&graph, folder_idx), NodeKind::Folder);
 
-    let without = graph_to_dot(&filter_graph(&graph, true, true, false, true, true, &[]));
+    let without = graph_to_dot(&filter_graph(&graph, &everything()));
     assert!(without.contains("foo/bar.js"));
     assert!(!without.contains("shape=folder"));
 
-    let with = graph_to_dot(&filter_graph(&graph, true, true, true, true, true, &[]));
+    let with = graph_to_dot(&filter_graph(&graph, &FilterOptions { include_folders: true, ..everything() }));
     assert!(with.contains("shape=folder"));
 }
 
@@ -279,9 +292,9 @@ fn test_asset_filter() {
     assert!(graph.find_edge(js_idx, css_idx).is_some());
     assert_eq!(resolve_node_kind(&graph, css_idx), NodeKind::Asset);
 
-    let without = graph_to_dot(&filter_graph(&graph, true, true, false, false, true, &[]));
+    let without = graph_to_dot(&filter_graph(&graph, &FilterOptions { include_assets: false, ..everything() }));
     assert!(!without.contains("style.css"));
-    let with = graph_to_dot(&filter_graph(&graph, true, true, false, true, true, &[]));
+    let with = graph_to_dot(&filter_graph(&graph, &everything()));
     assert!(with.contains("style.css"));
 }
 
@@ -292,7 +305,7 @@ fn test_json_output() {
     let logger = EmptyLogger;
     let walk = WalkBuilder::new(&root).build();
     let graph = build_dependency_graph(&walk, None, &logger).unwrap();
-    let json = graph_to_json(&filter_graph(&graph, true, true, false, true, true, &[]));
+    let json = graph_to_json(&filter_graph(&graph, &everything()));
     assert!(json.contains("index.js"));
     assert!(json.contains("b.js"));
 }
@@ -304,10 +317,7 @@ fn test_ignore_nodes() {
     let logger = EmptyLogger;
     let walk = WalkBuilder::new(&root).build();
     let graph = build_dependency_graph(&walk, None, &logger).unwrap();
-    let dot = graph_to_dot(&filter_graph(
-        &graph, true, true, false, true, true,
-        &["b.js".to_string()],
-    ));
+    let dot = graph_to_dot(&filter_graph(&graph, &FilterOptions { ignore_nodes: vec!["b.js".to_string()], ..everything() }));
     assert!(dot.contains("a.js"));
     assert!(!dot.contains("b.js"));
 }
@@ -380,7 +390,7 @@ fn test_json_output_from_json_mod() {
     let logger = EmptyLogger;
     let walk = WalkBuilder::new(&root).build();
     let graph = build_dependency_graph(&walk, None, &logger).unwrap();
-    let json = graph_to_json(&filter_graph(&graph, true, true, false, true, true, &[]));
+    let json = graph_to_json(&filter_graph(&graph, &everything()));
     assert!(json.contains("index.js"));
     assert!(json.contains("b.js"));
 }
@@ -394,3 +404,917 @@ proptest! {
         prop_assert!(dep_core::js_resolve::resolve_relative_import(&dir, "./foo").is_some());
     }
 }
+
+#[test]
+fn test_edge_import_kind_and_location() {
+    let fs = TestFS::new([
+        ("a.js", "import './b.js';\nconst c = require('./c.js');"),
+        ("b.js", ""),
+        ("c.js", ""),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+    let a_idx = graph.node_indices().find(|i| graph[*i].name == "a.js").unwrap();
+    let c_idx = graph.node_indices().find(|i| graph[*i].name == "c.js").unwrap();
+    let edge = graph.find_edge(a_idx, c_idx).unwrap();
+    assert_eq!(graph[edge].import_kind, Some(ImportKind::Require));
+    let loc = graph[edge].location.as_ref().unwrap();
+    assert_eq!((loc.file.as_str(), loc.line, loc.column), ("a.js", 2, 19));
+
+    let json = graph_to_json(&filter_graph(&graph, &everything()));
+    assert!(json.contains("\"import_kind\": \"SideEffect\""));
+    assert!(json.contains("\"line\": 2"));
+    let dot = graph_to_dot(&graph);
+    assert!(dot.contains("tooltip=\"a.js:2:19\""));
+}
+
+#[test]
+fn test_dynamic_import_edge_is_lazy() {
+    let fs = TestFS::new([
+        ("main.js", "// require('./dead.js');\nconst page = () => import('./page.js');"),
+        ("page.js", ""),
+        ("dead.js", ""),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+    let main_idx = graph.node_indices().find(|i| graph[*i].name == "main.js").unwrap();
+    let page_idx = graph.node_indices().find(|i| graph[*i].name == "page.js").unwrap();
+    let dead_idx = graph.node_indices().find(|i| graph[*i].name == "dead.js").unwrap();
+    let edge = graph.find_edge(main_idx, page_idx).unwrap();
+    assert_eq!(graph[edge].import_kind, Some(ImportKind::Dynamic));
+    assert!(graph.find_edge(main_idx, dead_idx).is_none());
+    assert!(graph_to_dot(&graph).contains("style=dotted"));
+}
+
+#[test]
+fn test_type_only_import_filter() {
+    let fs = TestFS::new([
+        ("a.ts", "import type { B } from './b';\nimport { c } from './c';"),
+        ("b.ts", "import { a } from './a';"),
+        ("c.ts", ""),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+    let find = |g: &petgraph::graph::DiGraph<dep::Node, dep::EdgeData>, from: &str, to: &str| {
+        let f = g.node_indices().find(|i| g[*i].name == from).unwrap();
+        let t = g.node_indices().find(|i| g[*i].name == to).unwrap();
+        g.find_edge(f, t).is_some()
+    };
+    let runtime = filter_graph(&graph, &FilterOptions { imports: ImportFilter::Runtime, ..everything() });
+    assert!(!find(&runtime, "a.ts", "b.ts"));
+    assert!(find(&runtime, "a.ts", "c.ts"));
+    assert!(find(&runtime, "b.ts", "a.ts"));
+
+    let types = filter_graph(&graph, &FilterOptions { imports: ImportFilter::Types, ..everything() });
+    assert!(find(&types, "a.ts", "b.ts"));
+    assert!(!find(&types, "a.ts", "c.ts"));
+}
+
+#[test]
+fn test_symbol_users_through_barrel() {
+    let fs = TestFS::new([
+        ("utils/date.ts", "export function formatDate() {}\nexport const parseDate = 1;"),
+        ("utils/index.ts", "export * from './date';"),
+        ("app.ts", "import { formatDate } from './utils';\nformatDate();"),
+        ("other.ts", "import { parseDate as p } from './utils/date';"),
+        ("page.mdx", "import { formatDate } from './utils'\n\n# Today"),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let sym_idx = graph
+        .node_indices()
+        .find(|i| graph[*i].name == "utils/date.ts#formatDate")
+        .unwrap();
+    assert_eq!(resolve_node_kind(&graph, sym_idx), NodeKind::Symbol);
+
+    let mut users: Vec<_> = symbol_users(&graph, "utils/date.ts", "formatDate")
+        .iter()
+        .map(|u| graph[u.user].name.clone())
+        .collect();
+    users.sort();
+    assert_eq!(users, ["app.ts", "page.mdx"]);
+
+    let users: Vec<_> = symbol_users(&graph, "utils/date.ts", "parseDate")
+        .iter()
+        .map(|u| graph[u.user].name.clone())
+        .collect();
+    assert_eq!(users, ["other.ts"]);
+
+    let symbols = symbol_graph(&graph);
+    let app = symbols.node_indices().find(|i| symbols[*i].name == "app.ts").unwrap();
+    let sym = symbols
+        .node_indices()
+        .find(|i| symbols[*i].name == "utils/date.ts#formatDate")
+        .unwrap();
+    assert!(symbols.find_edge(app, sym).is_some());
+
+    let dot = graph_to_dot(&filter_graph(&graph, &everything()));
+    assert!(!dot.contains("#formatDate"));
+}
+
+#[test]
+fn test_unused_exports_through_barrels() {
+    let fs = TestFS::new([
+        ("lib/math.ts", "export const add = 1;\nexport const sub = 2;\nexport default 3;"),
+        ("lib/index.ts", "export * from './math';\nexport const version = 1;"),
+        ("app.ts", "import { add } from './lib';"),
+        ("entry.ts", "export { sub } from './lib/math';\nexport const main = 1;"),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let names = |entries: &[String]| {
+        unused_exports(&graph, entries)
+            .iter()
+            .map(|u| format!("{}#{}", u.file, u.name))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(&[]),
+        ["entry.ts#main", "lib/index.ts#version", "lib/math.ts#sub", "lib/math.ts#default"]
+    );
+    assert_eq!(
+        names(&["entry.ts".to_string()]),
+        ["lib/index.ts#version", "lib/math.ts#default"]
+    );
+
+    let unused = unused_exports(&graph, &[]);
+    let loc = unused[0].location.as_ref().unwrap();
+    assert_eq!((loc.line, loc.column), (2, 14));
+}
+
+#[test]
+fn test_find_cycles_ignores_folders_and_type_imports() {
+    let fs = TestFS::new([
+        ("a.ts", "import { b } from './lib';"),
+        ("lib/index.ts", "export { b } from './b';"),
+        ("lib/b.ts", "import '../a';\nexport const b = 1;"),
+        ("x.ts", "import type { Y } from './y';\nexport type X = 1;"),
+        ("y.ts", "import type { X } from './x';\nexport type Y = 1;"),
+        ("z.ts", "import './a';"),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let cycles: Vec<_> = find_cycles(&graph, ImportFilter::All)
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    assert_eq!(
+        cycles,
+        ["a.ts -> lib/index.ts -> lib/b.ts -> a.ts", "x.ts -> y.ts -> x.ts"]
+    );
+
+    let runtime = find_cycles(&graph, ImportFilter::Runtime);
+    assert_eq!(runtime.len(), 1);
+    assert_eq!(runtime[0].files, ["a.ts", "lib/b.ts", "lib/index.ts"]);
+}
+
+#[test]
+fn test_dependents_skip_folder_edges() {
+    let fs = TestFS::new([
+        ("src/util.ts", "export const u = 1;"),
+        ("src/page.ts", "import { u } from './util';"),
+        ("src/index.ts", "export * from './page';"),
+        ("app.ts", "\nimport './src';"),
+        ("src/other.ts", "export const o = 1;"),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let found = dependents(&graph, "src/util.ts", None, ImportFilter::All).unwrap();
+    let files: Vec<_> = found.iter().map(|d| (d.file.as_str(), d.depth)).collect();
+    assert_eq!(files, [("src/page.ts", 1), ("src/index.ts", 2), ("app.ts", 3)]);
+    let chain: Vec<_> = found[2].chain.iter().map(|h| h.to_string()).collect();
+    assert_eq!(
+        chain,
+        ["app.ts:2:8 -> src/index.ts", "src/index.ts:1:15 -> src/page.ts", "src/page.ts:1:19 -> src/util.ts"]
+    );
+
+    let none = dependents(&graph, "src/other.ts", None, ImportFilter::All).unwrap();
+    assert!(none.is_empty());
+}
+
+#[test]
+fn test_import_paths_explain_builtin() {
+    let fs = TestFS::new([
+        ("main.ts", "import { read } from './io';\nimport type { T } from './types';"),
+        ("io/index.ts", "export * from './read';"),
+        ("io/read.ts", "import { readFile } from 'fs';\nexport const read = readFile;"),
+        ("types.ts", "import type { Stats } from 'node:fs';\nexport type T = Stats;"),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let from = ["main.ts".to_string()];
+    let paths = import_paths(&graph, &from, "node:fs", ImportFilter::Runtime);
+    assert_eq!(paths.len(), 1);
+    let hops: Vec<_> = paths[0].iter().map(|h| h.to_string()).collect();
+    assert_eq!(
+        hops,
+        ["main.ts:1:22 -> io/index.ts", "io/index.ts:1:15 -> io/read.ts", "io/read.ts:1:26 -> fs"]
+    );
+    assert_eq!(paths[0][2].import_kind, Some(ImportKind::Static));
+
+    let all = import_paths(&graph, &from, "fs", ImportFilter::All);
+    let targets: Vec<_> = all.iter().map(|p| p.last().unwrap().to.as_str()).collect();
+    assert_eq!(targets, ["node:fs", "fs"]);
+}
+
+#[test]
+fn test_check_rules_from_config() {
+    #[derive(serde::Deserialize)]
+    struct Config {
+        rules: Vec<Rule>,
+    }
+    let config: Config = toml::from_str(
+        r#"
+        [[rules]]
+        name = "ui-no-server"
+        from = ["src/ui/**"]
+        deny = ["src/server"]
+        allow = ["src/server/types.ts"]
+
+        [[rules]]
+        from = ["**"]
+        deny = ["@internal/legacy"]
+        "#,
+    )
+    .unwrap();
+    let fs = TestFS::new([
+        ("src/ui/button.ts", "import type { T } from '../server/types';\nimport { db } from '../server';"),
+        ("src/server/index.ts", "export const db = 1;"),
+        ("src/server/types.ts", "export type T = 1;"),
+        ("src/app.ts", "import old from '@internal/legacy/old';"),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let found: Vec<_> = check_rules(&graph, &config.rules, ImportFilter::All)
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+    assert_eq!(
+        found,
+        [
+            "[ui-no-server] src/ui/button.ts:2:20 -> src/server/index.ts",
+            "[#2] src/app.ts:1:17 -> @internal/legacy/old",
+        ]
+    );
+}
+
+#[test]
+fn test_dead_files_from_inferred_entrypoints() {
+    let fs = TestFS::new([
+        ("package.json", r#"{"name":"app","main":"./src/index.ts"}"#),
+        ("src/index.ts", "import './used';"),
+        ("src/used.ts", ""),
+        ("src/orphan.ts", "import './orphan.css';"),
+        ("src/orphan.css", ""),
+        ("public/index.html", "<script type=\"module\" src=\"./page.ts\"></script>"),
+        ("public/page.ts", ""),
+        ("src/used.spec.ts", "import './used';"),
+        ("scripts/build.ts", ""),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let entries = find_entrypoints(&graph, &["scripts/*.ts".to_string()], true);
+    let dead: Vec<_> = dead_files(&graph, &entries, ImportFilter::All)
+        .iter()
+        .map(|i| graph[*i].name.clone())
+        .collect();
+    assert_eq!(dead, ["src/orphan.css", "src/orphan.ts"]);
+}
+
+#[test]
+fn test_package_exports_and_imports_resolution() {
+    let fs = TestFS::new([
+        (
+            "package.json",
+            r##"{"name":"@acme/app",
+                "exports":{".":"./src/index.ts","./utils/*":{"browser":"./src/utils/*.browser.ts","default":"./src/utils/*.ts"}},
+                "imports":{"#db":{"development":"./src/db.dev.ts","default":"./src/db.ts"},"#dep":"lodash"}}"##,
+        ),
+        ("src/index.ts", "import '@acme/app/utils/date';\nimport '#db';\nimport '#dep';\nimport '#missing';"),
+        ("src/utils/date.ts", ""),
+        ("src/utils/date.browser.ts", ""),
+        ("src/db.ts", ""),
+        ("src/db.dev.ts", ""),
+        ("src/cli.ts", "import '@acme/app';"),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+
+    let targets = |graph: &DiGraph<dep::Node, dep::EdgeData>, from: &str| {
+        let idx = graph.node_indices().find(|i| graph[*i].name == from).unwrap();
+        let mut names: Vec<_> = graph
+            .edges(idx)
+            .filter(|e| e.weight().import_kind.is_some())
+            .map(|e| graph[e.target()].name.clone())
+            .collect();
+        names.sort();
+        names
+    };
+
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+    assert_eq!(targets(&graph, "src/index.ts"), ["lodash", "src/db.ts", "src/utils/date.ts", "unresolved:#missing"]);
+    assert_eq!(targets(&graph, "src/cli.ts"), ["src/index.ts"]);
+
+    let options = dep::js_resolve::ResolveOptions {
+        conditions: vec!["browser".into(), "development".into(), "import".into()],
+    };
+    let graph = build_dependency_graph_with(&walk, None, &options, &logger).unwrap();
+    assert_eq!(
+        targets(&graph, "src/index.ts"),
+        ["lodash", "src/db.dev.ts", "src/utils/date.browser.ts", "unresolved:#missing"]
+    );
+}
+
+#[test]
+fn test_workspace_package_imports_resolve_to_source() {
+    let fs = TestFS::new([
+        (
+            "packages/app/package.json",
+            r#"{"name":"@acme/app","dependencies":{"@acme/ui":"workspace:*","@acme/icons":"workspace:^"}}"#,
+        ),
+        (
+            "packages/app/src/main.ts",
+            "import { Button } from '@acme/ui';\nimport '@acme/ui/src/theme';\nimport star from '@acme/icons/star';\nimport 'react';",
+        ),
+        (
+            "packages/ui/package.json",
+            r#"{"name":"@acme/ui","main":"./dist/index.js","module":"./src/index.ts"}"#,
+        ),
+        ("packages/ui/src/index.ts", "export const Button = 1;"),
+        ("packages/ui/src/theme.ts", ""),
+        (
+            "packages/icons/package.json",
+            r#"{"name":"@acme/icons","exports":{"./*":{"import":"./dist/*.js"}},"types":"./src/index.ts"}"#,
+        ),
+        ("packages/icons/src/index.ts", ""),
+        ("packages/icons/src/star.tsx", ""),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let find = |name: &str| graph.node_indices().find(|i| graph[*i].name == name).unwrap();
+    let main = find("packages/app/src/main.ts");
+    let mut targets: Vec<_> = graph
+        .edges(main)
+        .filter(|e| e.weight().import_kind.is_some())
+        .map(|e| graph[e.target()].name.clone())
+        .collect();
+    targets.sort();
+    assert_eq!(
+        targets,
+        ["@acme/icons/star", "packages/ui/src/index.ts", "packages/ui/src/theme.ts", "react"]
+    );
+
+    let ui = find("@acme/ui");
+    assert_eq!(resolve_node_kind(&graph, ui), NodeKind::Package);
+    assert!(graph.find_edge(ui, find("packages/ui/src/index.ts")).is_some());
+}
+
+#[test]
+fn test_workspace_members_from_pnpm_workspace() {
+    let fs = TestFS::new([
+        ("package.json", r#"{"name":"root","private":true}"#),
+        ("pnpm-workspace.yaml", "packages:\n  - 'packages/*'\n"),
+        ("packages/ui/package.json", r#"{"name":"ui","main":"./index.js"}"#),
+        ("packages/ui/index.js", ""),
+        ("packages/ui/test/fixture/package.json", r#"{"name":"fixture","main":"./main.js"}"#),
+        ("packages/ui/test/fixture/main.js", ""),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let find = |name: &str| graph.node_indices().find(|i| graph[*i].name == name);
+    let ui = find("ui").unwrap();
+    assert_eq!(resolve_node_kind(&graph, ui), NodeKind::Package);
+    assert!(graph.find_edge(find("pnpm-workspace.yaml").unwrap(), ui).is_some());
+    assert!(graph.find_edge(ui, find("packages/ui/index.js").unwrap()).is_some());
+    assert!(find("fixture").is_none());
+}
+
+#[test]
+fn test_missing_and_unused_dependencies() {
+    let fs = TestFS::new([
+        ("pnpm-workspace.yaml", "packages:\n  - packages/*\n"),
+        (
+            "packages/app/package.json",
+            r#"{"name":"app","dependencies":{"lodash":"^4.17.0","left-pad":"1.3.0"},"devDependencies":{"@types/react":"^18.0.0","vitest":"^1.0.0"}}"#,
+        ),
+        (
+            "packages/app/src/main.ts",
+            "import fp from 'lodash/fp';\nimport { x } from '@scope/pkg/sub';\nimport type { FC } from 'react';\nimport { Button } from 'ui';\nimport fs from 'node:fs';",
+        ),
+        ("packages/ui/package.json", r#"{"name":"ui","main":"./src/index.ts"}"#),
+        ("packages/ui/src/index.ts", "export const Button = 1;"),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let report = dependency_report(&graph, ImportFilter::All);
+    let missing: Vec<_> = report
+        .missing
+        .iter()
+        .map(|m| (m.package.as_str(), m.dependency.as_str(), m.import.location.as_ref().unwrap().line))
+        .collect();
+    assert_eq!(missing, [("app", "@scope/pkg", 2), ("app", "ui", 4)]);
+    let unused: Vec<_> = report
+        .unused
+        .iter()
+        .map(|u| (u.package.as_str(), u.dependency.as_str(), u.kind))
+        .collect();
+    assert_eq!(
+        unused,
+        [("app", "left-pad", dep::DependencyKind::Prod), ("app", "vitest", dep::DependencyKind::Dev)]
+    );
+
+    let mut filtered = report.clone();
+    filtered.filter(&[dep::DependencyKind::Dev], &["@scope/*".to_string()]);
+    let missing: Vec<_> = filtered.missing.iter().map(|m| m.dependency.as_str()).collect();
+    assert_eq!(missing, ["ui"]);
+    let unused: Vec<_> = filtered.unused.iter().map(|u| u.dependency.as_str()).collect();
+    assert_eq!(unused, ["vitest"]);
+
+    let mut filtered = report;
+    filtered.filter(&[], &["vitest".to_string()]);
+    let unused: Vec<_> = filtered.unused.iter().map(|u| u.dependency.as_str()).collect();
+    assert_eq!(unused, ["left-pad"]);
+}
+
+#[test]
+fn test_cdn_scripts_are_not_dependencies() {
+    let fs = TestFS::new([
+        ("package.json", r#"{"name":"app","dependencies":{"lit":"^3.0.0"}}"#),
+        (
+            "index.html",
+            r#"<script src="https://cdn.example.com/analytics.js"></script>
+<link rel="stylesheet" href="//fonts.example.com/inter.css">
+<script type="module">import { html } from 'lit';</script>"#,
+        ),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let page = graph.node_indices().find(|i| graph[*i].name == "index.html").unwrap();
+    let cdn = graph
+        .node_indices()
+        .find(|i| graph[*i].name == "https://cdn.example.com/analytics.js")
+        .unwrap();
+    assert!(graph.find_edge(page, cdn).is_some());
+    assert_eq!(dependency_report(&graph, ImportFilter::All), dep::DependencyReport::default());
+}
+
+#[test]
+fn test_lockfile_versions_and_duplicates() {
+    let fs = TestFS::new([
+        ("package.json", r#"{"name":"root","private":true}"#),
+        ("pnpm-workspace.yaml", "packages:\n  - packages/*\n"),
+        ("packages/app/package.json", r#"{"name":"app","dependencies":{"debug":"^2.6.0","send":"^0.18.0"}}"#),
+        ("packages/app/index.js", "import debug from 'debug';"),
+        (
+            "pnpm-lock.yaml",
+            "lockfileVersion: '9.0'
+importers:
+  .: {}
+  packages/app:
+    dependencies:
+      debug:
+        specifier: ^2.6.0
+        version: 2.6.9
+      send:
+        specifier: ^0.18.0
+        version: 0.18.0
+packages:
+  debug@2.6.9:
+    resolution: {integrity: sha512-a}
+snapshots:
+  debug@2.6.9:
+    dependencies:
+      ms: 2.0.0
+  ms@2.0.0: {}
+  ms@2.1.3: {}
+  send@0.18.0:
+    dependencies:
+      debug: 2.6.9
+      ms: 2.1.3
+",
+        ),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let find = |name: &str| graph.node_indices().find(|i| graph[*i].name == name).unwrap();
+    let debug = find("debug@2.6.9");
+    assert_eq!(resolve_node_kind(&graph, debug), NodeKind::External);
+    assert!(graph.find_edge(find("debug"), debug).is_some());
+    assert!(graph.find_edge(find("app"), debug).is_some());
+    let ms = graph.find_edge(debug, find("ms@2.0.0")).unwrap();
+    assert_eq!(graph[ms].dependency.as_ref().unwrap().version, "2.0.0");
+
+    let duplicates = duplicate_packages(&graph);
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].name, "ms");
+    let versions: Vec<_> = duplicates[0]
+        .versions
+        .iter()
+        .map(|v| (v.version.as_str(), v.dependents.clone()))
+        .collect();
+    assert_eq!(
+        versions,
+        [("2.0.0", vec!["debug@2.6.9".to_string()]), ("2.1.3", vec!["send@0.18.0".to_string()])]
+    );
+
+    // The lockfile and workspace file are read, not imported
+    let entries = find_entrypoints(&graph, &[], false);
+    let dead: Vec<_> = dead_files(&graph, &entries, ImportFilter::All)
+        .into_iter()
+        .map(|i| graph[i].name.as_str())
+        .collect();
+    assert_eq!(dead, ["packages/app/index.js"]);
+}
+
+#[test]
+fn test_package_boundary_violations() {
+    let fs = TestFS::new([
+        ("package.json", r#"{"name":"root","private":true,"workspaces":["packages/*"]}"#),
+        ("tsconfig.json", r#"{"compilerOptions":{"baseUrl":".","paths":{"@ui/*":["packages/ui/src/*"]}}}"#),
+        ("shared.ts", ""),
+        ("packages/app/package.json", r#"{"name":"@acme/app","dependencies":{"@acme/ui":"workspace:*"}}"#),
+        (
+            "packages/app/src/main.ts",
+            "import { Button } from '@acme/ui';\nimport { theme } from '../../ui/src/theme';\nimport { Icon } from '@ui/icon';\nimport './local';\nimport '../../../shared';",
+        ),
+        ("packages/app/src/local.ts", ""),
+        ("packages/ui/package.json", r#"{"name":"@acme/ui","main":"./src/index.ts"}"#),
+        ("packages/ui/src/index.ts", "export const Button = 1;"),
+        ("packages/ui/src/theme.ts", "export const theme = 1;"),
+        ("packages/ui/src/icon.ts", "export const Icon = 1;"),
+    ]);
+    let root = fs.root();
+    let logger = EmptyLogger;
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &logger).unwrap();
+
+    let violations = boundary_violations(&graph, ImportFilter::All);
+    let found: Vec<_> = violations
+        .iter()
+        .map(|v| {
+            let loc = v.import.location.as_ref().unwrap();
+            (v.package.as_str(), v.target.as_str(), v.specifier.as_str(), loc.line)
+        })
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("@acme/app", "@acme/ui", "../../ui/src/theme", 2),
+            ("@acme/app", "@acme/ui", "@ui/icon", 3),
+        ]
+    );
+}
+
+#[test]
+fn test_tsconfig_extends_targets_and_nested_configs() {
+    let fs = TestFS::new([
+        ("node_modules/@acme/tsconfig/tsconfig.json", r#"{"compilerOptions":{"strict":true}}"#),
+        (
+            "tsconfig.base.json",
+            r#"{"extends":"@acme/tsconfig","compilerOptions":{"paths":{"@shared/*":["./shared/*","./generated/*"]}}}"#,
+        ),
+        ("tsconfig.json", r#"{"extends":"./tsconfig.base.json"}"#),
+        (
+            "apps/web/tsconfig.json",
+            r#"{"extends":"../../tsconfig.base.json","compilerOptions":{"baseUrl":".","paths":{"@/*":["src/*"],"@feature/*/api":["src/features/*/api"]},"rootDirs":["src","gen"]}}"#,
+        ),
+        ("shared/format.ts", ""),
+        ("generated/schema.ts", ""),
+        ("index.ts", "import '@shared/format';\nimport '@shared/schema';\nimport '@/main';"),
+        ("apps/web/src/main.ts", "import '@/ui';\nimport '@feature/cart/api';\nimport './types';"),
+        ("apps/web/src/ui.ts", ""),
+        ("apps/web/src/features/cart/api.ts", ""),
+        ("apps/web/gen/types.ts", ""),
+    ]);
+    let root = fs.root();
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &EmptyLogger).unwrap();
+    let edge = |from: &str, to: &str| {
+        let from = graph.node_indices().find(|i| graph[*i].name == from).unwrap();
+        graph
+            .node_indices()
+            .find(|i| graph[*i].name == to)
+            .is_some_and(|to| graph.find_edge(from, to).is_some())
+    };
+    // Every target is tried in order; root files use the root tsconfig
+    assert!(edge("index.ts", "shared/format.ts"));
+    assert!(edge("index.ts", "generated/schema.ts"));
+    assert!(edge("index.ts", "@/main"));
+    // Files of the app use its own tsconfig, which replaces the inherited paths
+    assert!(edge("apps/web/src/main.ts", "apps/web/src/ui.ts"));
+    assert!(edge("apps/web/src/main.ts", "apps/web/src/features/cart/api.ts"));
+    assert!(edge("apps/web/src/main.ts", "apps/web/gen/types.ts"));
+}
+
+#[test]
+fn test_esm_js_extension_resolves_to_ts_source() {
+    let fs = TestFS::new([
+        ("tsconfig.json", r#"{"compilerOptions":{"module":"nodenext","moduleSuffixes":[".web",""],"allowImportingTsExtensions":true}}"#),
+        ("src/main.ts", "import { a } from './a.js';\nimport './b.mjs';\nimport './c.ts';\nimport './view.js';"),
+        ("src/a.ts", ""),
+        ("src/b.mts", ""),
+        ("src/c.ts", ""),
+        ("src/view.ts", ""),
+        ("src/view.web.tsx", ""),
+    ]);
+    let root = fs.root();
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &EmptyLogger).unwrap();
+    let main = graph.node_indices().find(|i| graph[*i].name == "src/main.ts").unwrap();
+    let mut targets: Vec<_> = graph
+        .neighbors(main)
+        .map(|i| graph[i].name.as_str())
+        .filter(|n| n.starts_with("src/"))
+        .collect();
+    targets.sort();
+    assert_eq!(targets, ["src/a.ts", "src/b.mts", "src/c.ts", "src/view.web.tsx"]);
+}
+
+#[test]
+fn test_unresolved_imports_are_kept() {
+    let fs = TestFS::new([
+        ("package.json", r##"{"name":"app","imports":{"#config":"./config.js"}}"##),
+        ("config.js", ""),
+        ("src/a.ts", "import './b';\nimport '../lib/missing';\nimport '#config';\nimport '#nope';"),
+        ("src/b.ts", ""),
+        ("index.html", "<script src=\"./gone.js\"></script>"),
+    ]);
+    let root = fs.root();
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &EmptyLogger).unwrap();
+    let missing = graph.node_indices().find(|i| graph[*i].name == "unresolved:lib/missing").unwrap();
+    assert_eq!(resolve_node_kind(&graph, missing), NodeKind::Unresolved);
+    // Unresolved imports are not files and get no folder
+    assert!(graph.node_indices().all(|i| graph[i].name != "lib"));
+
+    let unresolved: Vec<_> = unresolved_imports(&graph, ImportFilter::All)
+        .iter()
+        .map(|u| u.to_string())
+        .collect();
+    assert_eq!(
+        unresolved,
+        [
+            "index.html:1:14: cannot resolve './gone.js'",
+            "src/a.ts:2:8: cannot resolve '../lib/missing'",
+            "src/a.ts:4:8: cannot resolve '#nope'",
+        ]
+    );
+}
+
+#[test]
+fn test_import_of_folder_without_index() {
+    let fs = TestFS::new([
+        ("src/main.ts", "import './components';\nimport './ui';"),
+        ("src/components/Button.ts", ""),
+        ("src/ui/index.ts", ""),
+    ]);
+    let root = fs.root();
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &EmptyLogger).unwrap();
+    let find = |name: &str| graph.node_indices().find(|i| graph[*i].name == name).unwrap();
+
+    // The folder node is untouched and the import gets a node of its own
+    assert_eq!(resolve_node_kind(&graph, find("src/components")), NodeKind::Folder);
+    let missing = find("unresolved:src/components");
+    assert_eq!(resolve_node_kind(&graph, missing), NodeKind::Unresolved);
+    assert!(graph.find_edge(find("src/main.ts"), missing).is_some());
+    // A folder with an index file resolves to the folder
+    assert!(graph.find_edge(find("src/main.ts"), find("src/ui")).is_some());
+
+    let unresolved: Vec<_> = unresolved_imports(&graph, ImportFilter::All)
+        .iter()
+        .map(|u| u.to_string())
+        .collect();
+    assert_eq!(unresolved, ["src/main.ts:1:8: cannot resolve './components'"]);
+}
+
+#[test]
+fn test_stylesheet_references() {
+    let fs = TestFS::new([
+        ("package.json", r#"{"name":"app","main":"./src/index.ts"}"#),
+        ("src/index.ts", "import './styles/main.scss';\nimport styles from './button.module.css';"),
+        ("src/styles/main.scss", "@use 'theme';\n.hero { background: url('../img/hero.png'); }"),
+        ("src/styles/_theme.scss", "@font-face { src: url(../fonts/inter.woff2?v=4); }"),
+        ("src/button.module.css", ".button { composes: base from './base.css'; }"),
+        ("src/base.css", ""),
+        ("src/img/hero.png", ""),
+        ("src/legacy.css", ".old { background: url(img/unused.png); }"),
+        ("src/img/unused.png", ""),
+        ("src/fonts/inter.woff2", ""),
+        ("src/orphan.css", ".plain { color: red; }"),
+    ]);
+    let root = fs.root();
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &EmptyLogger).unwrap();
+    let edge = |from: &str, to: &str| {
+        let from = graph.node_indices().find(|i| graph[*i].name == from).unwrap();
+        let to = graph.node_indices().find(|i| graph[*i].name == to).unwrap();
+        graph.find_edge(from, to).map(|e| graph[e].import_kind)
+    };
+    assert_eq!(edge("src/styles/main.scss", "src/styles/_theme.scss"), Some(Some(ImportKind::Style)));
+    assert_eq!(edge("src/styles/main.scss", "src/img/hero.png"), Some(Some(ImportKind::Url)));
+    assert_eq!(edge("src/styles/_theme.scss", "src/fonts/inter.woff2"), Some(Some(ImportKind::Url)));
+    assert_eq!(edge("src/button.module.css", "src/base.css"), Some(Some(ImportKind::Style)));
+
+    let entries = find_entrypoints(&graph, &[], true);
+    let dead: Vec<_> = dead_files(&graph, &entries, ImportFilter::All)
+        .iter()
+        .map(|i| graph[*i].name.clone())
+        .collect();
+    // Images only used by a stylesheet nothing loads are dead along with it
+    assert_eq!(dead, ["src/img/unused.png", "src/legacy.css", "src/orphan.css"]);
+
+    // A stylesheet is an asset even with no references and no importer
+    let orphan = graph.node_indices().find(|i| graph[*i].name == "src/orphan.css").unwrap();
+    assert_eq!(resolve_node_kind(&graph, orphan), NodeKind::Asset);
+    let without = graph_to_dot(&filter_graph(&graph, &FilterOptions { include_assets: false, ..everything() }));
+    assert!(!without.contains("orphan.css"));
+}
+
+#[test]
+fn test_vue_single_file_components() {
+    let fs = TestFS::new([
+        ("package.json", r#"{"name":"app","main":"./src/main.ts"}"#),
+        ("src/main.ts", "import { createApp } from 'vue';\nimport App from './App';\ncreateApp(App);"),
+        (
+            "src/App.vue",
+            "<script setup lang=\"ts\">\nimport Header from './components/Header.vue';\nimport { useStore } from '@/store';\n</script>\n<template><Header /></template>\n<style lang=\"scss\">\n@use './styles/theme';\n</style>",
+        ),
+        ("src/components/Header.vue", "<script>\nexport default {};\n</script>\n<style src=\"./header.css\"></style>"),
+        ("src/components/header.css", ""),
+        ("src/store.ts", "export const useStore = () => {};"),
+        ("src/styles/_theme.scss", ""),
+        ("tsconfig.json", r#"{"compilerOptions":{"paths":{"@/*":["./src/*"]}}}"#),
+    ]);
+    let root = fs.root();
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &EmptyLogger).unwrap();
+    let edge = |from: &str, to: &str| {
+        let from = graph.node_indices().find(|i| graph[*i].name == from).unwrap();
+        let to = graph.node_indices().find(|i| graph[*i].name == to).unwrap();
+        graph.find_edge(from, to).map(|e| graph[e].import_kind)
+    };
+    assert_eq!(edge("src/main.ts", "src/App.vue"), Some(Some(ImportKind::Static)));
+    assert_eq!(edge("src/App.vue", "src/components/Header.vue"), Some(Some(ImportKind::Static)));
+    assert_eq!(edge("src/App.vue", "src/store.ts"), Some(Some(ImportKind::Static)));
+    assert_eq!(edge("src/App.vue", "src/styles/_theme.scss"), Some(Some(ImportKind::Style)));
+    assert_eq!(
+        edge("src/components/Header.vue", "src/components/header.css"),
+        Some(Some(ImportKind::Style))
+    );
+
+    let entries = find_entrypoints(&graph, &[], true);
+    assert!(dead_files(&graph, &entries, ImportFilter::All).is_empty());
+}
+
+#[test]
+fn test_svelte_and_astro_components() {
+    let fs = TestFS::new([
+        (
+            "src/pages/index.astro",
+            "---\nimport Base from '../layouts/Base.astro';\nimport Counter from '../components/Counter.svelte';\n---\n<Base><Counter client:load /></Base>\n<script>\nimport '../lib/theme-toggle';\n</script>",
+        ),
+        ("src/layouts/Base.astro", "---\nconst { title } = Astro.props;\n---\n<html><slot /></html>"),
+        (
+            "src/components/Counter.svelte",
+            "<script lang=\"ts\">\nimport { count } from '../lib/stores';\nexport let step = 1;\n</script>\n<button on:click={() => count.update((n) => n + step)}>{$count}</button>",
+        ),
+        ("src/lib/stores.ts", "export const count = 0;"),
+        ("src/lib/theme-toggle.ts", ""),
+        ("src/lib/unused.ts", ""),
+    ]);
+    let root = fs.root();
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &EmptyLogger).unwrap();
+    let edge = |from: &str, to: &str| {
+        let from = graph.node_indices().find(|i| graph[*i].name == from).unwrap();
+        let to = graph.node_indices().find(|i| graph[*i].name == to).unwrap();
+        graph.find_edge(from, to).is_some()
+    };
+    assert!(edge("src/pages/index.astro", "src/layouts/Base.astro"));
+    assert!(edge("src/pages/index.astro", "src/components/Counter.svelte"));
+    assert!(edge("src/pages/index.astro", "src/lib/theme-toggle.ts"));
+    assert!(edge("src/components/Counter.svelte", "src/lib/stores.ts"));
+
+    let entries = find_entrypoints(&graph, &["src/pages/index.astro".to_string()], false);
+    let dead: Vec<_> = dead_files(&graph, &entries, ImportFilter::All)
+        .iter()
+        .map(|i| graph[*i].name.clone())
+        .collect();
+    assert_eq!(dead, ["src/lib/unused.ts"]);
+}
+
+#[test]
+fn test_html_resource_references() {
+    let fs = TestFS::new([
+        (
+            "index.html",
+            "<!doctype html>\n<link rel=\"icon\" href=\"/favicon.svg\">\n<link rel=stylesheet href=src/style.css>\n<script data-note=\"a > b\" type=\"module\" src=\"/src/main.ts\"></script>\n<script type=\"module\">\nimport { track } from './src/analytics';\ntrack();\n</script>\n<img src=\"src/img/logo.png\" srcset=\"src/img/logo@2x.png 2x\">",
+        ),
+        ("favicon.svg", ""),
+        ("src/style.css", ""),
+        ("src/main.ts", ""),
+        ("src/analytics.ts", ""),
+        ("src/img/logo.png", ""),
+        ("src/img/logo@2x.png", ""),
+        ("src/unused.ts", ""),
+    ]);
+    let root = fs.root();
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &EmptyLogger).unwrap();
+    let edge = |to: &str| {
+        let from = graph.node_indices().find(|i| graph[*i].name == "index.html").unwrap();
+        let to = graph.node_indices().find(|i| graph[*i].name == to).unwrap();
+        graph.find_edge(from, to).map(|e| graph[e].import_kind)
+    };
+    assert_eq!(edge("favicon.svg"), Some(Some(ImportKind::Url)));
+    assert_eq!(edge("src/style.css"), Some(Some(ImportKind::Style)));
+    assert_eq!(edge("src/main.ts"), Some(Some(ImportKind::Script)));
+    assert_eq!(edge("src/analytics.ts"), Some(Some(ImportKind::Static)));
+    assert_eq!(edge("src/img/logo@2x.png"), Some(Some(ImportKind::Url)));
+
+    let entries = find_entrypoints(&graph, &["index.html".to_string()], false);
+    let dead: Vec<_> = dead_files(&graph, &entries, ImportFilter::All)
+        .iter()
+        .map(|i| graph[*i].name.clone())
+        .collect();
+    assert_eq!(dead, ["src/unused.ts"]);
+}
+
+#[test]
+fn test_import_maps() {
+    let fs = TestFS::new([
+        (
+            "web/index.html",
+            "<script type=\"importmap\">{\"imports\":{\"lit\":\"/web/vendor/lit.js\",\"@app/\":\"./src/\",\"preact\":\"https://esm.sh/preact\"}}</script>\n<script type=\"module\" src=\"./src/main.js\"></script>",
+        ),
+        ("web/src/main.js", "import { html } from 'lit';\nimport '@app/ui/button.js';\nimport 'preact';"),
+        ("web/src/ui/button.js", ""),
+        ("web/vendor/lit.js", ""),
+        ("tools/importmap.json", r#"{"imports":{"shared/":"../shared/"}}"#),
+        ("tools/cli.js", "import 'shared/log.js';"),
+        ("shared/log.js", ""),
+        ("api/deno.json", r#"{"imports":{"@std/assert":"jsr:@std/assert@^1.0.0","@/":"./"}}"#),
+        ("api/main.ts", "import { assert } from '@std/assert';\nimport { db } from '@/db.ts';"),
+        ("api/db.ts", "export const db = {};"),
+    ]);
+    let root = fs.root();
+    let walk = WalkBuilder::new(&root).build();
+    let graph = build_dependency_graph(&walk, None, &EmptyLogger).unwrap();
+    let targets = |from: &str| -> Vec<String> {
+        let from = graph.node_indices().find(|i| graph[*i].name == from).unwrap();
+        let mut targets: Vec<_> = graph
+            .edges(from)
+            .filter(|e| e.weight().import_kind.is_some())
+            .map(|e| graph[e.target()].name.clone())
+            .collect();
+        targets.sort();
+        targets
+    };
+    assert_eq!(targets("web/src/main.js"), ["preact", "web/src/ui/button.js", "web/vendor/lit.js"]);
+    assert_eq!(targets("tools/cli.js"), ["shared/log.js"]);
+    assert_eq!(targets("api/main.ts"), ["@std/assert", "api/db.ts"]);
+}